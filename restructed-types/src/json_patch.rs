@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+/// An operation of an RFC 6902 JSON Patch document (`application/json-patch+json`), deserialized from objects like
+/// `{"op": "replace", "path": "/email", "value": "a@x"}`.
+///
+/// Patches with `json_patch = true` are built from a list of these with `from_json_patch`, which supports `add`,
+/// `replace`, `remove` and `test` on the top-level fields of the patch and rejects the rest.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    /// Sets the value at `path`
+    Add { path: String, value: Value },
+    /// Removes the value at `path`
+    Remove { path: String },
+    /// Replaces the value at `path`
+    Replace { path: String, value: Value },
+    /// Moves the value at `from` to `path`
+    Move { from: String, path: String },
+    /// Copies the value at `from` to `path`
+    Copy { from: String, path: String },
+    /// Checks the value at `path` equals `value`
+    Test { path: String, value: Value },
+}
+
+impl Operation {
+    /// The name of the operation, as in its `op` member
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Add { .. } => "add",
+            Self::Remove { .. } => "remove",
+            Self::Replace { .. } => "replace",
+            Self::Move { .. } => "move",
+            Self::Copy { .. } => "copy",
+            Self::Test { .. } => "test",
+        }
+    }
+
+    /// The JSON Pointer the operation targets
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Add { path, .. }
+            | Self::Remove { path }
+            | Self::Replace { path, .. }
+            | Self::Move { path, .. }
+            | Self::Copy { path, .. }
+            | Self::Test { path, .. } => path,
+        }
+    }
+}
+
+/// The member a JSON Pointer (RFC 6901) refers to when it points at a member of the root object, e.g. `email` for
+/// `/email`. `None` for the root itself and for anything nested deeper.
+pub fn top_level_member(pointer: &str) -> Option<String> {
+    let token = pointer.strip_prefix('/')?;
+    match token.contains('/') {
+        true => None,
+        false => Some(token.replace("~1", "/").replace("~0", "~")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{top_level_member, Operation};
+    use serde_json::json;
+
+    #[test]
+    fn operations_and_pointers() {
+        let operation: Operation = serde_json::from_value(json!({ "op": "remove", "path": "/a~1b" })).unwrap();
+        assert_eq!(operation, Operation::Remove { path: "/a~1b".to_string() });
+        assert_eq!((operation.name(), operation.path()), ("remove", "/a~1b"));
+
+        assert_eq!(top_level_member("/a~1b").as_deref(), Some("a/b"));
+        assert_eq!(top_level_member("/~01").as_deref(), Some("~1"));
+        assert_eq!(top_level_member(""), None);
+        assert_eq!(top_level_member("/address/city"), None);
+    }
+}
@@ -0,0 +1,18 @@
+//! Runtime types for the models derived by [restructed](https://docs.rs/restructed).
+//!
+//! [`Maybe`] is the tri-state option used by patches with `option = Maybe`, telling a field left out (undefined)
+//! apart from one explicitly set to `null`. Other wrappers are used with `option = path::to::Wrapper` by
+//! implementing [`PatchOption`].
+//!
+//! With the `json-patch` feature, [`json_patch::Operation`] holds the RFC 6902 operations patches with
+//! `json_patch = true` are built from.
+
+#[cfg(feature = "json-patch")]
+pub mod json_patch;
+mod maybe;
+mod nested;
+mod option;
+
+pub use maybe::Maybe;
+pub use nested::NestedMerge;
+pub use option::PatchOption;
@@ -0,0 +1,162 @@
+/// A value that may be left out (undefined), explicitly cleared (null) or set, i.e. the tri-state of a JSON field.
+///
+/// With the `serde` feature, a missing field deserializes to [`Maybe::Undefined`] (given `#[serde(default)]`) and
+/// `null` to [`Maybe::Null`]. Serializing skips undefined fields with
+/// `#[serde(skip_serializing_if = "Maybe::is_undefined")]`, patches deriving serde get both attributes generated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Maybe<T> {
+    /// The value was left out, so it's left unchanged
+    #[default]
+    Undefined,
+    /// The value was explicitly cleared
+    Null,
+    /// The value was set
+    Value(T),
+}
+
+impl<T> Maybe<T> {
+    /// Returns `true` if the value was left out
+    pub const fn is_undefined(&self) -> bool {
+        matches!(self, Self::Undefined)
+    }
+
+    /// Returns `true` if the value was explicitly cleared
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns `true` if the value was set
+    pub const fn is_value(&self) -> bool {
+        matches!(self, Self::Value(_))
+    }
+
+    /// The value if it was set
+    pub const fn value(&self) -> Option<&T> {
+        match self {
+            Self::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Converts from `&Maybe<T>` to `Maybe<&T>`
+    pub const fn as_ref(&self) -> Maybe<&T> {
+        match self {
+            Self::Undefined => Maybe::Undefined,
+            Self::Null => Maybe::Null,
+            Self::Value(v) => Maybe::Value(v),
+        }
+    }
+
+    /// Maps the value if it was set
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Maybe<U> {
+        match self {
+            Self::Undefined => Maybe::Undefined,
+            Self::Null => Maybe::Null,
+            Self::Value(v) => Maybe::Value(f(v)),
+        }
+    }
+
+    /// Takes the value out, leaving [`Maybe::Undefined`] in its place
+    pub fn take(&mut self) -> Self {
+        std::mem::take(self)
+    }
+
+    /// `None` when undefined, `Some(None)` when null and `Some(Some(value))` when set
+    pub fn into_option(self) -> Option<Option<T>> {
+        self.into()
+    }
+
+    /// `Null` for `None` and `Value` for `Some`, i.e. the state of a field that's always present
+    pub fn from_option(value: Option<T>) -> Self {
+        match value {
+            Some(v) => Self::Value(v),
+            None => Self::Null,
+        }
+    }
+
+    /// Updates `target` when defined, setting it to `None` when null
+    pub fn update(self, target: &mut Option<T>) {
+        match self {
+            Self::Undefined => {}
+            Self::Null => *target = None,
+            Self::Value(v) => *target = Some(v),
+        }
+    }
+}
+
+impl<T> From<Option<Option<T>>> for Maybe<T> {
+    fn from(value: Option<Option<T>>) -> Self {
+        match value {
+            None => Self::Undefined,
+            Some(None) => Self::Null,
+            Some(Some(v)) => Self::Value(v),
+        }
+    }
+}
+
+impl<T> From<Maybe<T>> for Option<Option<T>> {
+    fn from(value: Maybe<T>) -> Self {
+        match value {
+            Maybe::Undefined => None,
+            Maybe::Null => Some(None),
+            Maybe::Value(v) => Some(Some(v)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Maybe<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // Undefined fields are meant to be skipped, otherwise they're as good as null
+            Self::Undefined | Self::Null => serializer.serialize_none(),
+            Self::Value(v) => serializer.serialize_some(v),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Maybe<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Missing fields never get here, they're filled in by `#[serde(default)]` as `Undefined`
+        Option::<T>::deserialize(deserializer).map(Self::from_option)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Maybe;
+
+    #[test]
+    fn option_conversions() {
+        assert_eq!(Maybe::<u8>::from(None), Maybe::Undefined);
+        assert_eq!(Maybe::from(Some(None::<u8>)), Maybe::Null);
+        assert_eq!(Maybe::from(Some(Some(1))).into_option(), Some(Some(1)));
+
+        let mut target = Some(1);
+        Maybe::Undefined.update(&mut target);
+        assert_eq!(target, Some(1));
+        Maybe::Null.update(&mut target);
+        assert_eq!(target, None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_missing_and_null() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Update {
+            #[serde(default, skip_serializing_if = "Maybe::is_undefined")]
+            bio: Maybe<String>,
+        }
+
+        let missing: Update = serde_json::from_str("{}").unwrap();
+        assert_eq!(missing.bio, Maybe::Undefined);
+        let null: Update = serde_json::from_str(r#"{"bio":null}"#).unwrap();
+        assert_eq!(null.bio, Maybe::Null);
+        let value: Update = serde_json::from_str(r#"{"bio":"hi"}"#).unwrap();
+        assert_eq!(value.bio, Maybe::Value("hi".to_string()));
+
+        assert_eq!(serde_json::to_string(&missing).unwrap(), "{}");
+        assert_eq!(serde_json::to_string(&null).unwrap(), r#"{"bio":null}"#);
+    }
+}
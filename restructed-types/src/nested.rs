@@ -0,0 +1,16 @@
+/// What merging a nested patch returns, which has to be `()` as the patch holding it merges infallibly (besides
+/// `nested_none = "error"`). Patches that can fail to merge themselves, i.e. ones with `nested_none = "error"` and
+/// nested fields of their own, can't be nested.
+#[diagnostic::on_unimplemented(
+    message = "nested patches must merge infallibly, but this one returns `{Self}`",
+    label = "the nested patch can fail to merge",
+    note = "patches with `nested_none = \"error\"` and nested fields of their own can't be nested"
+)]
+pub trait NestedMerge {
+    /// Finishes the merge of the nested patch
+    fn finish(self);
+}
+
+impl NestedMerge for () {
+    fn finish(self) {}
+}
@@ -0,0 +1,158 @@
+use crate::Maybe;
+
+/// A field of a patch that's either undefined (left unchanged), null (cleared) or a value, used by patches with
+/// `option = path::to::Wrapper` to merge into and convert from `Option` fields of the original.
+///
+/// Implemented for [`Maybe`] and `Option<Option<T>>` (where `None` is undefined), along with the wrappers of other
+/// crates behind their features. Wrappers of crates not covered here can be used through a newtype implementing
+/// this trait.
+pub trait PatchOption: Sized {
+    /// The type of the value when set
+    type Value;
+
+    /// The wrapper when the value was left out
+    fn undefined() -> Self;
+
+    /// The wrapper holding the state of an `Option`, i.e. null for `None` and a value for `Some`
+    fn from_option(value: Option<Self::Value>) -> Self;
+
+    /// The wrapper holding the original's field when converting the original into a patch, the same as
+    /// [`PatchOption::from_option`] unless the wrapper leaves `None` undefined instead
+    fn from_original(value: Option<Self::Value>) -> Self {
+        Self::from_option(value)
+    }
+
+    /// The state of the wrapper
+    fn into_maybe(self) -> Maybe<Self::Value>;
+
+    /// The state of the wrapper by reference
+    fn as_maybe(&self) -> Maybe<&Self::Value>;
+
+    /// Whether the value was left out, e.g. to skip serializing it
+    fn is_undefined(&self) -> bool;
+}
+
+impl<T> PatchOption for Maybe<T> {
+    type Value = T;
+
+    fn undefined() -> Self {
+        Self::Undefined
+    }
+
+    fn from_option(value: Option<T>) -> Self {
+        Self::from_option(value)
+    }
+
+    fn into_maybe(self) -> Maybe<T> {
+        self
+    }
+
+    fn as_maybe(&self) -> Maybe<&T> {
+        self.as_ref()
+    }
+
+    fn is_undefined(&self) -> bool {
+        Self::is_undefined(self)
+    }
+}
+
+impl<T> PatchOption for Option<Option<T>> {
+    type Value = T;
+
+    fn undefined() -> Self {
+        None
+    }
+
+    fn from_option(value: Option<T>) -> Self {
+        Some(value)
+    }
+
+    fn into_maybe(self) -> Maybe<T> {
+        self.into()
+    }
+
+    fn as_maybe(&self) -> Maybe<&T> {
+        self.as_ref().map(Option::as_ref).into()
+    }
+
+    fn is_undefined(&self) -> bool {
+        self.is_none()
+    }
+}
+
+#[cfg(feature = "poem-openapi")]
+impl<T> PatchOption for poem_openapi::types::MaybeUndefined<T> {
+    type Value = T;
+
+    fn undefined() -> Self {
+        Self::Undefined
+    }
+
+    fn from_option(value: Option<T>) -> Self {
+        match value {
+            Some(v) => Self::Value(v),
+            None => Self::Null,
+        }
+    }
+
+    // Patches have always left `None` fields of the original undefined with this wrapper
+    fn from_original(value: Option<T>) -> Self {
+        Self::from_opt_undefined(value)
+    }
+
+    fn into_maybe(self) -> Maybe<T> {
+        match self {
+            Self::Undefined => Maybe::Undefined,
+            Self::Null => Maybe::Null,
+            Self::Value(v) => Maybe::Value(v),
+        }
+    }
+
+    fn as_maybe(&self) -> Maybe<&T> {
+        match self {
+            Self::Undefined => Maybe::Undefined,
+            Self::Null => Maybe::Null,
+            Self::Value(v) => Maybe::Value(v),
+        }
+    }
+
+    fn is_undefined(&self) -> bool {
+        Self::is_undefined(self)
+    }
+}
+
+#[cfg(feature = "async-graphql")]
+impl<T> PatchOption for async_graphql::MaybeUndefined<T> {
+    type Value = T;
+
+    fn undefined() -> Self {
+        Self::Undefined
+    }
+
+    fn from_option(value: Option<T>) -> Self {
+        match value {
+            Some(v) => Self::Value(v),
+            None => Self::Null,
+        }
+    }
+
+    fn into_maybe(self) -> Maybe<T> {
+        match self {
+            Self::Undefined => Maybe::Undefined,
+            Self::Null => Maybe::Null,
+            Self::Value(v) => Maybe::Value(v),
+        }
+    }
+
+    fn as_maybe(&self) -> Maybe<&T> {
+        match self {
+            Self::Undefined => Maybe::Undefined,
+            Self::Null => Maybe::Null,
+            Self::Value(v) => Maybe::Value(v),
+        }
+    }
+
+    fn is_undefined(&self) -> bool {
+        Self::is_undefined(self)
+    }
+}
@@ -5,12 +5,12 @@ mod patch;
 mod view;
 
 use crate::logic::is_attribute;
-use logic::args::{ExtraConditions, ModelAttrArgs};
+use logic::args::{ExtraConditions, FieldAttrArgs, ModelAttrArgs};
 use proc_macro::TokenStream;
-use proc_macro_error::proc_macro_error;
+use proc_macro_error::{abort, proc_macro_error};
 
 #[proc_macro_error]
-#[proc_macro_derive(Models, attributes(model, view, patch))]
+#[proc_macro_derive(Models, attributes(model, view, patch, kind))]
 pub fn models(input: TokenStream) -> TokenStream {
     let ast = syn::parse_macro_input!(input as syn::DeriveInput);
 
@@ -20,8 +20,10 @@ pub fn models(input: TokenStream) -> TokenStream {
         .cloned()
         .collect::<Vec<_>>();
 
-    let extras = ExtraConditions::parse(&ast.attrs);
+    let extras = ExtraConditions::parse(&ast);
     let model_args = ModelAttrArgs::parse(model_attr, extras);
+    FieldAttrArgs::abort_unknown_models(&ast, "view");
+    FieldAttrArgs::abort_unknown_models(&ast, "patch");
 
     let views: Vec<proc_macro2::TokenStream> = ast
         .attrs
@@ -37,9 +39,21 @@ pub fn models(input: TokenStream) -> TokenStream {
         .map(|a| patch::impl_patch_model(&ast, a, &model_args))
         .collect();
 
+    let kinds: Vec<proc_macro2::TokenStream> = ast
+        .attrs
+        .iter()
+        .filter(|v| is_attribute(v, "kind"))
+        .enumerate()
+        .map(|(i, a)| match i {
+            0 => view::impl_kind_model(&ast, a, &model_args),
+            _ => abort!(a, "Invalid attribute, expected only one `kind` attribute as it defines `kind(&self)`"),
+        })
+        .collect();
+
     let gen = quote::quote!(
         #(#views)*
         #(#patches)*
+        #(#kinds)*
     );
 
     gen.into()
@@ -1,10 +1,14 @@
 use super::{
-    abort_unexpected_args, extract_idents, has_oai_attribute, take_ident_group, take_ident_ident,
+    abort_unexpected_args, container_of, did_you_mean, extract_idents, field_member, take_ident_type,
+    has_oai_attribute, is_attribute, member_name, take_ident_bool, take_ident_group,
     take_ident_literal, take_path_group,
 };
-use proc_macro2::{Ident, TokenTree};
+use super::case::RenameRule;
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
 use proc_macro_error::abort;
-use syn::{Attribute, Field};
+use quote::{quote, ToTokens};
+use syn::{ext::IdentExt, parse::Parser, punctuated::Punctuated};
+use syn::{Attribute, Data, DeriveInput, Field, Member};
 
 #[derive(Clone, Default)]
 pub(crate) struct ModelAttrArgs {
@@ -22,7 +26,10 @@ impl ModelAttrArgs {
 
     pub(crate) fn parse(attrs: Vec<Attribute>, extras: ExtraConditions) -> Self {
         if attrs.is_empty() {
-            return Self::default();
+            return Self {
+                extras,
+                ..Default::default()
+            };
         } else if attrs.len() > 1 {
             abort!(
                 attrs[1],
@@ -49,6 +56,11 @@ impl ModelAttrArgs {
         let defaults = take_ident_group("defaults", args_mr)
             .map(|g| DefaultAttrArgs::parse(&mut g.stream().into_iter().collect(), attr));
 
+        base.iter()
+            .filter_map(|b| b.fields.as_ref())
+            .chain(defaults.iter().filter_map(|d| d.fields.as_ref()))
+            .for_each(|f| f.validate(&extras));
+
         Self::abort_unexpected(&args);
 
         Self {
@@ -127,12 +139,14 @@ pub(crate) struct AttrArgs {
     pub preset: Preset,
     /// Attributes to carry over onto the derived struct
     pub attributes_with: AttributesWith,
+    /// Fields to be renamed on the derived struct
+    pub rename: RenameArgs,
 }
 
 impl AttrArgs {
     /// Conditional aborts on unexpected args to show that they arent valid
     pub(crate) fn abort_unexpected(args: &[TokenTree], ignore: &[&str]) {
-        const EXPECTED: &[&str; 5] = &["fields", "omit", "derive", "attributes_with", "preset"];
+        const EXPECTED: &[&str; 7] = &["fields", "omit", "derive", "attributes_with", "preset", "rename", "rename_all"];
         let mut expect = EXPECTED.to_vec();
         expect.extend(ignore);
         abort_unexpected_args(expect, args)
@@ -144,36 +158,14 @@ impl AttrArgs {
         model_args: &ModelAttrArgs,
         abort_unexpected: bool,
     ) -> (Self, Vec<TokenTree>) {
-        let tks: Vec<TokenTree> = attr
-            .meta
-            .require_list()
-            .expect("This attribute must be in a list format")
-            .to_owned()
-            .tokens
-            .into_iter()
-            .collect::<Vec<_>>();
-
-        let name = match &tks[0] {
-            TokenTree::Ident(v) => v.clone(),
-            x => {
-                abort!(
-                    x,
-                    "First argument must be an identifier (name) of the struct for the view"
-                )
-            }
-        };
-
-        let mut args = match tks.len() < 3 {
-            true => vec![],
-            false => tks[2..].to_vec(),
-        };
-
+        let (name, mut args) = split_name(attr);
         let fields = FieldsArg::parse_with_args(&mut args, model_args, attr);
         let derive = parse_derives_wtih_args(&mut args, model_args);
         let preset = Preset::parse_with_args(&mut args, model_args);
         let attributes_with =
             AttributesWith::parse_with_args(&mut args, model_args, preset.as_ref())
                 .unwrap_or_default();
+        let rename = RenameArgs::parse(&mut args, model_args);
 
         if abort_unexpected {
             Self::abort_unexpected(&args, &[])
@@ -186,18 +178,591 @@ impl AttrArgs {
                 derive,
                 preset: preset.unwrap_or_default(),
                 attributes_with,
+                rename,
             },
             args,
         )
     }
+
+    /// Whether the field (or enum variant) is part of this model. Field level attributes (of the given `kind`) take
+    /// priority over the `fields`/`omit` selection of the model.
+    pub(crate) fn includes(&self, member: &Member, attrs: &[Attribute], kind: &str) -> bool {
+        FieldAttrArgs::parse(attrs, kind)
+            .predicate(&self.name)
+            .unwrap_or_else(|| self.fields.predicate(member))
+    }
+
+    /// Same as [`Self::includes`] for the field of an enum variant
+    pub(crate) fn includes_variant_field(&self, variant: &Ident, member: &Member, attrs: &[Attribute], kind: &str) -> bool {
+        FieldAttrArgs::parse(attrs, kind)
+            .predicate(&self.name)
+            .unwrap_or_else(|| self.fields.variant_field_predicate(variant, member))
+    }
+}
+
+/// Splits the arguments of a model attribute into the name of the model and the remaining arguments
+fn split_name(attr: &Attribute) -> (Ident, Vec<TokenTree>) {
+    let tks: Vec<TokenTree> = attr
+        .meta
+        .require_list()
+        .expect("This attribute must be in a list format")
+        .to_owned()
+        .tokens
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let name = match tks.first() {
+        Some(TokenTree::Ident(v)) => v.clone(),
+        Some(x) => abort!(x, "First argument must be an identifier (name) of the struct for the view"),
+        None => abort!(attr, "First argument must be an identifier (name) of the struct for the view"),
+    };
+
+    let args = match tks.len() < 3 {
+        true => vec![],
+        false => tks[2..].to_vec(),
+    };
+    (name, args)
+}
+
+/// Arguments of a kind model, e.g. `#[kind(EventKind, derive(Serialize), rename_all = "snake_case")]`. Kinds hold
+/// every variant and none of the attributes of the original, so only derives and renaming apply.
+#[derive(Clone)]
+pub(crate) struct KindArgs {
+    /// The name of the generated enum
+    pub name: Ident,
+    /// Traits to derive on top of the ones every kind derives
+    pub derive: Option<Vec<syn::Path>>,
+    /// Variants to be renamed on the generated enum
+    pub rename: RenameArgs,
+}
+
+impl KindArgs {
+    pub(crate) fn parse(attr: &Attribute, model_args: &ModelAttrArgs) -> Self {
+        let (name, mut args) = split_name(attr);
+        let derive = parse_derives_wtih_args(&mut args, model_args);
+        let rename = RenameArgs::parse(&mut args, model_args);
+        abort_unexpected_args(vec!["derive", "rename", "rename_all"], &args);
+
+        Self { name, derive, rename }
+    }
+}
+
+/// Renaming of fields (or variants for enums) on the generated model, e.g. `rename(password_hash => hash)` and/or `rename_all = "camelCase"`
+#[derive(Clone, Default)]
+pub(crate) struct RenameArgs {
+    /// Explicitly renamed fields as `(original, renamed)`, these take priority over `all`
+    pub fields: Vec<(Ident, Ident)>,
+    /// The casing to rename every other field to
+    pub all: Option<RenameRule>,
+}
+
+impl RenameArgs {
+    pub(crate) fn parse(args: &mut Vec<TokenTree>, model_args: &ModelAttrArgs) -> Self {
+        struct RenamePair(Ident, Ident);
+        impl syn::parse::Parse for RenamePair {
+            fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+                let original = input.parse()?;
+                input.parse::<syn::Token![=>]>()?;
+                Ok(Self(original, input.parse()?))
+            }
+        }
+
+        let fields: Vec<(Ident, Ident)> = take_ident_group("rename", args)
+            .map(|g| {
+                let parser = Punctuated::<RenamePair, syn::Token![,]>::parse_terminated;
+                match parser.parse2(g.stream()) {
+                    Ok(pairs) => pairs.into_iter().map(|p| (p.0, p.1)).collect(),
+                    Err(e) => abort!(e.span(), "Invalid syntax, expected renamed fields, e.g. `rename(password_hash => hash)`"),
+                }
+            })
+            .unwrap_or_default();
+        let all_literal = take_ident_literal("rename_all", args);
+        let all = all_literal.as_ref().map(RenameRule::parse);
+
+        for (original, renamed) in &fields {
+            model_args.extras.validate_member(&Member::Named(original.clone()));
+            if fields.iter().filter(|(_, r)| r == renamed).count() > 1 {
+                abort!(renamed, "Duplicate name, more than one field is renamed to `{}`", renamed)
+            }
+        }
+        if !model_args.extras.members.iter().all(|m| matches!(m, Member::Named(_))) {
+            match (fields.first(), all_literal) {
+                (Some((original, _)), _) => abort!(original, "Tuple struct fields have no names to rename"),
+                (None, Some(literal)) => abort!(literal, "Tuple struct fields have no names to rename"),
+                (None, None) => {}
+            }
+        }
+
+        Self { fields, all }
+    }
+
+    /// The identifier the field (or variant) should have on the generated model
+    pub(crate) fn rename(&self, ident: &Ident) -> Ident {
+        match self.fields.iter().find(|(original, _)| original == ident) {
+            Some((_, renamed)) => renamed.clone(),
+            None => match self.all {
+                Some(rule) => {
+                    let renamed = rule.apply(&ident.unraw().to_string());
+                    match syn::parse_str::<Ident>(&renamed) {
+                        Ok(_) => Ident::new(&renamed, ident.span()),
+                        // Keywords can only be used as raw identifiers, bar the few that can't be raw at all
+                        Err(_) if ["self", "Self", "super", "crate"].contains(&renamed.as_str()) => {
+                            abort!(ident, "`{}` would be renamed to the keyword `{}`, rename it explicitly instead", ident, renamed)
+                        }
+                        Err(_) => Ident::new_raw(&renamed, ident.span()),
+                    }
+                }
+                None => ident.clone(),
+            },
+        }
+    }
+
+    /// Aborts when a renamed field isn't one of the given members the model has or two fields end up with the same name
+    pub(crate) fn validate(&self, members: &[Member]) {
+        for (original, _) in &self.fields {
+            if !members.contains(&Member::Named(original.clone())) {
+                abort!(original, "Field `{}` can't be renamed as it isn't part of this model", original)
+            }
+        }
+
+        let mut renamed: Vec<Ident> = vec![];
+        for member in members {
+            if let Member::Named(ident) = member {
+                let name = self.rename(ident);
+                if renamed.contains(&name) {
+                    abort!(name, "Duplicate name, more than one field would be named `{}`", name)
+                }
+                renamed.push(name);
+            }
+        }
+    }
+
+    /// Lints to allow on the generated model, since `rename_all` may produce names not following rust's casing
+    pub(crate) fn gen_allow_lints(&self) -> TokenStream {
+        match self.all {
+            Some(_) => quote!(#[allow(non_snake_case, non_camel_case_types)]),
+            None => quote!(),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// A field mapped to another type on a view, e.g. `map(created_at: String = to_rfc3339)` or with a reverse
+/// function `map(created_at: String = (to_rfc3339, from_rfc3339))`. The functions are given a reference to the value
+/// (so `ToString::to_string` works as is), without a function the value is converted by `Into`.
+#[derive(Clone)]
+pub(crate) struct MapArg {
+    /// The variant holding the field, only for enums, i.e. `Variant::field`
+    pub variant: Option<Ident>,
+    /// The field being mapped
+    pub field: Member,
+    /// The type of the field on the view
+    pub ty: syn::Type,
+    /// Converts the original field into the view's field
+    pub forward: Option<syn::Expr>,
+    /// Converts the view's field back into the original field
+    pub reverse: Option<syn::Expr>,
+}
+
+impl syn::parse::Parse for MapArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let FieldPath { variant, member: field } = input.parse()?;
+        input.parse::<syn::Token![:]>()?;
+        let ty = input.parse()?;
+
+        let (forward, reverse) = match input.parse::<Option<syn::Token![=]>>()? {
+            Some(_) => match input.parse::<syn::Expr>()? {
+                syn::Expr::Tuple(t) if t.elems.len() == 2 => {
+                    let mut elems = t.elems.into_iter();
+                    (elems.next(), elems.next())
+                }
+                expr => (Some(expr), None),
+            },
+            None => (None, None),
+        };
+
+        Ok(Self { variant, field, ty, forward, reverse })
+    }
+}
+
+impl MapArg {
+    pub(crate) fn parse(args: &mut Vec<TokenTree>, model_args: &ModelAttrArgs) -> Vec<Self> {
+        let Some(g) = take_ident_group("map", args) else {
+            return vec![];
+        };
+
+        let parser = Punctuated::<MapArg, syn::Token![,]>::parse_terminated;
+        let maps: Vec<Self> = match parser.parse2(g.stream()) {
+            Ok(maps) => maps.into_iter().collect(),
+            Err(e) => abort!(e.span(), "Invalid syntax, expected mapped fields, e.g. `map(id: String = ToString::to_string)`: {}", e),
+        };
+
+        for map in &maps {
+            match (&map.variant, model_args.extras.is_enum) {
+                (None, true) => abort!(g, "Fields of enum variants are mapped by `Variant::field`, e.g. `map(Card::last4: String)`"),
+                (variant, _) => model_args.extras.validate_path(&FieldPath { variant: variant.clone(), member: map.field.clone() }),
+            }
+        }
+        maps
+    }
+
+    /// Finds the mapping of a field, `variant` being `None` for structs
+    pub(crate) fn find<'a>(maps: &'a [Self], variant: Option<&Ident>, field: &Member) -> Option<&'a Self> {
+        maps.iter().find(|m| m.variant.as_ref() == variant && m.field == *field)
+    }
+
+    /// Converts the original field (given as an expression) into the view's field
+    pub(crate) fn gen_forward(&self, value: TokenStream) -> TokenStream {
+        match &self.forward {
+            Some(forward) => quote!((#forward)(&#value)),
+            None => quote!(::core::convert::Into::into(#value)),
+        }
+    }
+
+    /// Same as [`Self::gen_forward`] for a value behind a reference, which is cloned when converted by [`Into`]
+    pub(crate) fn gen_forward_borrowed(&self, value: TokenStream) -> TokenStream {
+        match &self.forward {
+            Some(_) => self.gen_forward(value),
+            None => self.gen_forward(quote!(::core::clone::Clone::clone(&#value))),
+        }
+    }
+
+    /// Converts the view's field (given as an expression) back into the original field, if a reverse was given
+    pub(crate) fn gen_reverse(&self, value: TokenStream) -> Option<TokenStream> {
+        self.reverse.as_ref().map(|reverse| quote!((#reverse)(&#value)))
+    }
+}
+
+/// A field computed from the original for a view, e.g. `compute(full_name: String = User::full_name)`.
+/// The function is given a reference to the original before any of its fields are moved into the view.
+#[derive(Clone)]
+pub(crate) struct ComputeArg {
+    /// The name of the field on the view
+    pub name: Ident,
+    /// The type of the field on the view
+    pub ty: syn::Type,
+    /// Computes the field's value from a reference to the original
+    pub function: syn::Expr,
+}
+
+impl syn::parse::Parse for ComputeArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        Ok(Self { name, ty, function: input.parse()? })
+    }
+}
+
+impl ComputeArg {
+    pub(crate) fn parse(args: &mut Vec<TokenTree>, model_args: &ModelAttrArgs) -> Vec<Self> {
+        let Some(g) = take_ident_group("compute", args) else {
+            return vec![];
+        };
+
+        let parser = Punctuated::<ComputeArg, syn::Token![,]>::parse_terminated;
+        let computed: Vec<Self> = match parser.parse2(g.stream()) {
+            Ok(computed) => computed.into_iter().collect(),
+            Err(e) => abort!(e.span(), "Invalid syntax, expected computed fields, e.g. `compute(full_name: String = User::full_name)`: {}", e),
+        };
+
+        if model_args.extras.is_enum {
+            abort!(g, "Computed fields are only supported on struct views")
+        } else if model_args.extras.members.iter().any(|m| matches!(m, Member::Unnamed(_))) {
+            abort!(g, "Computed fields need a name so aren't supported on tuple structs")
+        }
+        computed
+    }
+
+    /// Aborts when a computed field has the same name as another field of the view
+    pub(crate) fn validate(computed: &[Self], names: &[Ident]) {
+        for (i, c) in computed.iter().enumerate() {
+            if names.contains(&c.name) || computed[..i].iter().any(|v| v.name == c.name) {
+                abort!(c.name, "Duplicate name, the view already has a field named `{}`", c.name)
+            }
+        }
+    }
+
+    /// Computes the field's value from the original (given as an expression)
+    pub(crate) fn gen_value(&self, original: TokenStream) -> TokenStream {
+        let function = &self.function;
+        quote!((#function)(&#original))
+    }
+}
+
+/// A field whose type has its own model, e.g. `nested(address = AddressPatch)` on patches so only the fields the nested
+/// patch sets are merged into it, or `nested(addresses = AddressPublic)` on views to project each child
+#[derive(Clone)]
+pub(crate) struct NestedArg {
+    /// The field holding the nested model
+    pub field: Member,
+    /// The model of the field's type, e.g. of the type in its `Option` or of the elements of a view's collection
+    pub model: syn::Type,
+}
+
+impl syn::parse::Parse for NestedArg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let field = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        Ok(Self { field, model: input.parse()? })
+    }
+}
+
+impl NestedArg {
+    /// Parses the nested models of a model of the given `kind` (i.e. `view` or `patch`)
+    pub(crate) fn parse(args: &mut Vec<TokenTree>, model_args: &ModelAttrArgs, kind: &str) -> Vec<Self> {
+        let Some(g) = take_ident_group("nested", args) else {
+            return vec![];
+        };
+
+        let parser = Punctuated::<NestedArg, syn::Token![,]>::parse_terminated;
+        let nested: Vec<Self> = match parser.parse2(g.stream()) {
+            Ok(nested) => nested.into_iter().collect(),
+            Err(e) => abort!(e.span(), "Invalid syntax, expected nested models, e.g. `nested(address = AddressModel)`: {}", e),
+        };
+
+        if model_args.extras.is_enum {
+            abort!(g, "Nested {} models are only supported on structs", kind)
+        }
+        for (i, n) in nested.iter().enumerate() {
+            model_args.extras.validate_member(&n.field);
+            if nested[..i].iter().any(|v| v.field == n.field) {
+                abort!(n.field, "Duplicate field, `{}` already has a nested model", member_name(&n.field))
+            }
+        }
+        nested
+    }
+
+    /// Aborts when a nested field isn't one of the given members the model (of the given `kind`) has
+    pub(crate) fn validate(nested: &[Self], members: &[Member], kind: &str) {
+        if let Some(n) = nested.iter().find(|n| !members.contains(&n.field)) {
+            abort!(n.field, "Field `{}` can't be nested as it isn't part of this {}", member_name(&n.field), kind)
+        }
+    }
+
+    /// Finds the nested model of a field
+    pub(crate) fn find<'a>(nested: &'a [Self], field: &Member) -> Option<&'a Self> {
+        nested.iter().find(|n| n.field == *field)
+    }
+
+    /// The type of a view's field with the original type replaced by the nested view, looking into `Option`, `Box`,
+    /// collections and the values of maps. Along with it converts the original field (given as an expression) into it
+    /// by `Into`, element-wise for collections.
+    pub(crate) fn gen_view(&self, ty: &syn::Type, value: TokenStream) -> (syn::Type, TokenStream) {
+        let (container, types) = container_of(ty).unwrap_or_default();
+        let (index, convert): (usize, fn(TokenStream, TokenStream) -> TokenStream) = match (container.as_str(), types.as_slice()) {
+            ("Option", [_]) => (0, |value, inner| quote!(#value.map(|v| #inner))),
+            ("Box", [_]) => (0, |value, inner| quote!({ let v = *#value; ::std::boxed::Box::new(#inner) })),
+            ("Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" | "BinaryHeap", [_, ..]) => {
+                (0, |value, inner| quote!(#value.into_iter().map(|v| #inner).collect()))
+            }
+            ("HashMap" | "BTreeMap" | "IndexMap", [_, _, ..]) => {
+                (1, |value, inner| quote!(#value.into_iter().map(|(k, v)| (k, #inner)).collect()))
+            }
+            _ => return (self.model.clone(), quote!(::core::convert::Into::into(#value))),
+        };
+
+        let (inner_ty, inner) = self.gen_view(types[index], quote!(v));
+        let mut view_ty = ty.clone();
+        if let syn::Type::Path(p) = &mut view_ty {
+            if let Some(syn::PathArguments::AngleBracketed(args)) = p.path.segments.last_mut().map(|s| &mut s.arguments) {
+                let arg = args
+                    .args
+                    .iter_mut()
+                    .filter(|a| matches!(a, syn::GenericArgument::Type(_)))
+                    .nth(index)
+                    .unwrap();
+                *arg = syn::GenericArgument::Type(inner_ty);
+            }
+        }
+        (view_ty, convert(value, inner))
+    }
+}
+
+/// Collection fields of a patch updated by operations (e.g. appending) instead of being replaced, e.g.
+/// `collections(tags, roles)`
+#[derive(Clone, Default)]
+pub(crate) struct CollectionsArg {
+    pub fields: Vec<Member>,
+}
+
+impl CollectionsArg {
+    pub(crate) fn parse(args: &mut Vec<TokenTree>, model_args: &ModelAttrArgs) -> Self {
+        let Some(g) = take_ident_group("collections", args) else {
+            return Self::default();
+        };
+
+        let parser = Punctuated::<Member, syn::Token![,]>::parse_terminated;
+        let fields: Vec<Member> = match parser.parse2(g.stream()) {
+            Ok(fields) => fields.into_iter().collect(),
+            Err(e) => abort!(e.span(), "Invalid syntax, expected collection fields, e.g. `collections(tags, roles)`: {}", e),
+        };
+
+        if model_args.extras.is_enum {
+            abort!(g, "Collection fields are only supported on struct patches")
+        }
+        fields.iter().for_each(|f| model_args.extras.validate_member(f));
+        Self { fields }
+    }
+
+    /// Aborts when a collection field isn't one of the given members the patch has
+    pub(crate) fn validate(&self, members: &[Member]) {
+        if let Some(field) = self.fields.iter().find(|f| !members.contains(f)) {
+            abort!(field, "Field `{}` can't be a collection as it isn't part of this patch", member_name(field))
+        }
+    }
+
+    pub(crate) fn contains(&self, field: &Member) -> bool {
+        self.fields.contains(field)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// What merging a nested patch into an `Option` field that's `None` does, e.g. `nested_none = "error"`
+#[derive(Clone, Copy, Default, PartialEq)]
+pub(crate) enum NestedNone {
+    /// Merges the nested patch into the `Default` of the field's type
+    #[default]
+    Default,
+    /// Returns an error, leaving the original untouched
+    Error,
+}
+
+impl NestedNone {
+    pub(crate) fn parse(args: &mut Vec<TokenTree>) -> Option<Self> {
+        let lit = take_ident_literal("nested_none", args)?;
+        Some(match lit.to_string().trim_matches('"') {
+            "default" => Self::Default,
+            "error" => Self::Error,
+            v => abort!(lit, "Invalid value, expected `default` or `error` but got `{}`", v),
+        })
+    }
+}
+
+/// Arguments for generating the borrowing version of a struct view, e.g. `ref = true, to_owned = true, ref_derive(Debug)`
+#[derive(Clone)]
+pub(crate) struct RefArgs {
+    /// Whether to generate a `to_owned()` cloning into the owned view
+    pub to_owned: bool,
+    /// Traits to derive on the ref view
+    pub derive: Option<Vec<syn::Path>>,
+}
+
+impl RefArgs {
+    /// Returns `None` when no ref view should be generated
+    pub(crate) fn parse(args: &mut Vec<TokenTree>, attr: &Attribute, model_args: &ModelAttrArgs) -> Option<Self> {
+        let is_ref = take_ident_bool("ref", args).unwrap_or_default();
+        let to_owned = take_ident_bool("to_owned", args).unwrap_or_default();
+        let derive = take_path_group("ref_derive", args);
+
+        if !is_ref && (to_owned || derive.is_some()) {
+            abort!(attr, "`to_owned` and `ref_derive` only apply to ref views, add `ref = true` to generate one")
+        } else if is_ref && model_args.extras.is_enum {
+            abort!(attr, "Ref views can only be derived for structs")
+        }
+        is_ref.then_some(Self { to_owned, derive })
+    }
+}
+
+/// Field level arguments, e.g. `#[view(skip(UserPublic))]` or `#[patch(only(UserUpdate, AdminUpdate))]`
+#[derive(Clone, Default)]
+pub(crate) struct FieldAttrArgs {
+    /// Models the field is never a part of
+    pub skip: Vec<Ident>,
+    /// Models the field is exclusively a part of
+    pub only: Option<Vec<Ident>>,
+}
+
+impl FieldAttrArgs {
+    /// Parses all the field's attributes of the given `kind` (i.e. `view` or `patch`)
+    pub(crate) fn parse(attrs: &[Attribute], kind: &str) -> Self {
+        let mut parsed = Self::default();
+
+        for attr in attrs.iter().filter(|a| is_attribute(a, kind)) {
+            let mut args: Vec<TokenTree> = match attr.meta.require_list() {
+                Ok(list) => list.tokens.clone().into_iter().collect(),
+                Err(_) => abort!(
+                    attr,
+                    "Invalid field attribute, expected a list of args, e.g. `#[{}(skip(...))]` or `#[{}(only(...))]`",
+                    kind,
+                    kind
+                ),
+            };
+
+            if let Some(g) = take_ident_group("skip", &mut args) {
+                parsed.skip.extend(extract_idents(g));
+            }
+            if let Some(g) = take_ident_group("only", &mut args) {
+                parsed.only.get_or_insert_with(Vec::new).extend(extract_idents(g));
+            }
+            abort_unexpected_args(vec!["skip", "only"], &args);
+        }
+
+        if let (Some(skip), Some(_)) = (parsed.skip.first(), &parsed.only) {
+            abort!(skip, "Cannot have both `skip` and `only` on the same field")
+        }
+        parsed
+    }
+
+    /// Whether the field is part of the given model, or `None` when the field doesn't decide it
+    pub(crate) fn predicate(&self, model: &Ident) -> Option<bool> {
+        if self.skip.contains(model) {
+            return Some(false);
+        }
+        self.only.as_ref().map(|only| only.contains(model))
+    }
+
+    /// Aborts on any model named that isn't declared on the container with an attribute of the given `kind`
+    pub(crate) fn abort_unknown_models(ast: &DeriveInput, kind: &str) {
+        let declared: Vec<Ident> = ast
+            .attrs
+            .iter()
+            .filter(|a| is_attribute(a, kind))
+            .filter_map(model_name)
+            .collect();
+
+        let attrs: Vec<&Vec<Attribute>> = match &ast.data {
+            Data::Struct(data) => data.fields.iter().map(|f| &f.attrs).collect(),
+            Data::Enum(data) => data
+                .variants
+                .iter()
+                .flat_map(|v| std::iter::once(&v.attrs).chain(v.fields.iter().map(|f| &f.attrs)))
+                .collect(),
+            Data::Union(_) => vec![],
+        };
+
+        for attrs in attrs {
+            let args = Self::parse(attrs, kind);
+            let named = args.skip.iter().chain(args.only.iter().flatten());
+            if let Some(unknown) = named.into_iter().find(|m| !declared.contains(m)) {
+                let declared: Vec<String> = declared.iter().map(|v| v.to_string()).collect();
+                match did_you_mean(&unknown.to_string(), &declared) {
+                    Some(suggestion) => abort!(unknown, "Unknown {} model `{}`, did you mean `{}`?", kind, unknown, suggestion),
+                    None => abort!(unknown, "Unknown {} model `{}`, models declared on this type are {:?}", kind, unknown, declared),
+                }
+            }
+        }
+    }
+}
+
+/// The name of the model an attribute declares, i.e. the first argument of `#[view(Name, ...)]`
+fn model_name(attr: &Attribute) -> Option<Ident> {
+    let list = attr.meta.require_list().ok()?;
+    match list.tokens.clone().into_iter().next() {
+        Some(TokenTree::Ident(name)) => Some(name),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
 pub(crate) enum FieldsArg {
     /// Fields to be included in the model (A whitelist)
-    Fields(Vec<Ident>),
+    Fields(Vec<FieldPath>),
     /// Fields to be omitted from the model (A blacklist)
-    Omit(Vec<Ident>),
+    Omit(Vec<FieldPath>),
 }
 
 impl FieldsArg {
@@ -209,8 +774,8 @@ impl FieldsArg {
 
         // Parse the fields argument into a TokenStream, skip checking for commas coz lazy
         match (field_arg, omit_args) {
-            (Some(g), None) => Self::Fields(extract_idents(g)),
-            (None, Some(g)) => Self::Omit(extract_idents(g)),
+            (Some(g), None) => Self::Fields(FieldPath::parse_group(g)),
+            (None, Some(g)) => Self::Omit(FieldPath::parse_group(g)),
             (None, None) => Self::default(),
             (Some(_), Some(_)) => abort!(
                 attr_spanned,
@@ -226,6 +791,7 @@ impl FieldsArg {
     ) -> Self {
         use FieldsArg::*;
         let fields = FieldsArg::parse(args, attr);
+        fields.validate(&model_args.extras);
 
         let default_fields = model_args.defaults.as_ref().and_then(|v| v.fields.clone());
         let fields = match &default_fields {
@@ -263,10 +829,67 @@ impl FieldsArg {
         }
     }
 
-    pub(crate) fn predicate(&self, field: &Ident) -> bool {
+    /// Aborts on any field (or variant for enums) named that doesn't exist on the deriving type
+    pub(crate) fn validate(&self, extras: &ExtraConditions) {
+        let paths = match self {
+            Self::Fields(paths) | Self::Omit(paths) => paths,
+        };
+        paths.iter().for_each(|p| extras.validate_path(p));
+    }
+
+    /// Whether the field (or variant for enums) is selected. Listing a field of a variant also selects the variant.
+    pub(crate) fn predicate(&self, field: &Member) -> bool {
+        match self {
+            Self::Fields(paths) => paths.iter().any(|p| match &p.variant {
+                Some(variant) => *field == Member::Named(variant.clone()),
+                None => p.member == *field,
+            }),
+            Self::Omit(paths) => !paths.iter().any(|p| p.variant.is_none() && p.member == *field),
+        }
+    }
+
+    /// Whether the field of an enum variant is selected, which is all of them when the variant is listed by itself
+    pub(crate) fn variant_field_predicate(&self, variant: &Ident, field: &Member) -> bool {
+        let is_named = |paths: &[FieldPath]| paths.iter().any(|p| p.variant.as_ref() == Some(variant) && p.member == *field);
         match self {
-            Self::Fields(fields) => fields.contains(field),
-            Self::Omit(fields) => !fields.contains(field),
+            Self::Fields(paths) => is_named(paths) || paths.contains(&FieldPath { variant: None, member: Member::Named(variant.clone()) }),
+            Self::Omit(paths) => !is_named(paths),
+        }
+    }
+}
+
+/// A field (or variant for enums) named by an argument, fields of enum variants are named as `Variant::field`
+#[derive(Clone, PartialEq)]
+pub(crate) struct FieldPath {
+    /// The variant holding the field, only for fields of enum variants
+    pub variant: Option<Ident>,
+    /// The field, or the variant itself when `variant` is `None` on enums
+    pub member: Member,
+}
+
+impl syn::parse::Parse for FieldPath {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let first: Member = input.parse()?;
+        match input.peek(syn::Token![::]) {
+            true => {
+                input.parse::<syn::Token![::]>()?;
+                match first {
+                    Member::Named(variant) => Ok(Self { variant: Some(variant), member: input.parse()? }),
+                    Member::Unnamed(i) => Err(syn::Error::new(i.span, "Expected a variant identifier")),
+                }
+            }
+            false => Ok(Self { variant: None, member: first }),
+        }
+    }
+}
+
+impl FieldPath {
+    /// Parses a list of fields, e.g. `fields(id, 0, Card::cvv)`
+    fn parse_group(group: Group) -> Vec<Self> {
+        let parser = Punctuated::<FieldPath, syn::Token![,]>::parse_terminated;
+        match parser.parse2(group.stream()) {
+            Ok(paths) => paths.into_iter().collect(),
+            Err(e) => abort!(e.span(), "Invalid syntax, expected a field identifier, tuple position or `Variant::field`: {}", e),
         }
     }
 }
@@ -327,8 +950,8 @@ impl AttributesWith {
                 .attrs
                 .iter()
                 .filter(|attr| {
-                    attr.path().segments.first().map_or(true, |seg| {
-                        !matches!(seg.ident.to_string().as_str(), "view" | "patch")
+                    attr.path().segments.first().is_none_or(|seg| {
+                        !matches!(seg.ident.to_string().as_str(), "view" | "patch" | "kind")
                     }) // update if we add more
                 })
                 .collect(),
@@ -336,8 +959,8 @@ impl AttributesWith {
                 .attrs
                 .iter()
                 .filter(|attr| {
-                    attr.path().segments.first().map_or(false, |seg| {
-                        !matches!(seg.ident.to_string().as_str(), "view" | "patch" | "derive")
+                    attr.path().segments.first().is_some_and(|seg| {
+                        !matches!(seg.ident.to_string().as_str(), "view" | "patch" | "kind" | "derive")
                     }) // update if we add more
                 })
                 .collect(),
@@ -361,33 +984,118 @@ impl AttributesWith {
                         .path()
                         .segments
                         .first()
-                        .map_or(false, |seg| seg.ident == "oai")
+                        .is_some_and(|seg| seg.ident == "oai")
                 })
                 .collect::<Vec<_>>(),
-            Self::All | AttributesWith::Deriveless => attrs.into_iter().collect::<Vec<_>>(), // change if we ever add field level attributes to this crate
+            Self::All | AttributesWith::Deriveless => attrs
+                .into_iter()
+                .filter(|attr| !is_attribute(attr, "view") && !is_attribute(attr, "patch"))
+                .collect::<Vec<_>>(),
             Self::None => vec![],
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+/// The wrapper of the patch's fields whose original is an `Option`, telling a field left out (undefined) apart from one
+/// that's cleared (null). Custom wrappers are merged and converted through `restructed_types::PatchOption`.
+#[derive(Clone, Default)]
 pub(crate) enum OptionType {
+    /// `Option<Option<T>>`, where `None` is undefined and `Some(None)` null
     #[default]
     Option,
+    /// `poem_openapi::types::MaybeUndefined<T>`
     MaybeUndefined,
+    /// Any other wrapper, as the type of the field with `_` in place of the original's `T`, e.g. `option = Maybe`,
+    /// `option = path::to::Wrapper` or `option = path::to::Wrapper<_, Extra>`
+    Custom(Box<syn::Type>),
 }
 
 impl OptionType {
     pub(crate) fn parse(args: &mut Vec<TokenTree>) -> Option<OptionType> {
-        let ident = take_ident_ident("option", args)?;
+        let ty = take_ident_type("option", args)?;
+        let syn::Type::Path(type_path) = &ty else {
+            abort!(ty, "Invalid `option` argument, expected a path or a type with `_` in place of the field's type, e.g. `option = Option<Option<_>>`")
+        };
+        let path = &type_path.path;
+        let is_infer = |ty: &syn::Type| matches!(ty, syn::Type::Infer(_));
 
-        Some(match ident.to_string().as_str() {
-            "Option" => OptionType::Option,
-            "MaybeUndefined" => OptionType::MaybeUndefined,
-            _ => abort!(
-                ident,
-                "Invalid type, expected `Option` or `MaybeUndefined` (from poem_openapi crate)"
-            ),
+        Some(match path.get_ident().map(ToString::to_string).as_deref() {
+            Some("Option") => OptionType::Option,
+            Some("MaybeUndefined") => OptionType::MaybeUndefined,
+            Some("Maybe") => OptionType::Custom(Box::new(syn::parse_quote!(::restructed_types::Maybe<_>))),
+            // Wrapping in a single `Option` can't be undefined, so e.g. `std::option::Option` is the default instead
+            _ if path.segments.last().is_some_and(|s| s.ident == "Option" && s.arguments.is_empty()) => OptionType::Option,
+            _ => match container_of(&ty) {
+                Some((outer, types)) if outer == "Option" => match types.as_slice() {
+                    [inner] if container_of(inner).is_some_and(|(c, t)| c == "Option" && matches!(t.as_slice(), [t] if is_infer(t))) => {
+                        OptionType::Option
+                    }
+                    _ => abort!(ty, "Invalid `option` argument, an `Option` wrapper must be `Option<Option<_>>`"),
+                },
+                _ if path.segments.last().is_some_and(|s| s.arguments.is_empty()) => {
+                    OptionType::Custom(Box::new(syn::parse_quote!(#path<_>)))
+                }
+                _ if contains_infer(type_path.to_token_stream()) => OptionType::Custom(Box::new(ty)),
+                _ => abort!(ty, "Invalid `option` argument, expected `_` in place of the field's type, e.g. `option = {}`", quote!(#path<_>)),
+            },
+        })
+    }
+
+    /// The type of the patch's field wrapping the type of the original's `Option`
+    pub(crate) fn wrap(&self, ty: &syn::Type) -> syn::Type {
+        match self {
+            Self::Option => syn::parse_quote!(::core::option::Option<::core::option::Option<#ty>>),
+            Self::MaybeUndefined => syn::parse_quote!(::poem_openapi::types::MaybeUndefined<#ty>),
+            Self::Custom(wrapper) => match syn::parse2(replace_infer(wrapper.to_token_stream(), ty)) {
+                Ok(ty) => ty,
+                Err(e) => abort!(wrapper, "Invalid `option` argument: {}", e),
+            },
+        }
+    }
+}
+
+/// Whether the tokens have a `_` anywhere, i.e. the placeholder of a wrapper's type
+fn contains_infer(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|tk| match tk {
+        TokenTree::Ident(i) => i == "_",
+        TokenTree::Group(g) => contains_infer(g.stream()),
+        _ => false,
+    })
+}
+
+/// Replaces every `_` of the tokens with the given type
+fn replace_infer(tokens: TokenStream, ty: &syn::Type) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|tk| match tk {
+            TokenTree::Ident(i) if i == "_" => ty.to_token_stream(),
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), replace_infer(g.stream(), ty));
+                group.set_span(g.span());
+                TokenTree::Group(group).into()
+            }
+            tk => tk.into(),
+        })
+        .collect()
+}
+
+/// What merging an enum patch does when its variant differs from the original's, e.g. `on_mismatch = "replace"`
+#[derive(Clone, Copy, Default, PartialEq)]
+pub(crate) enum OnMismatch {
+    /// Returns an error, leaving the original untouched
+    #[default]
+    Error,
+    /// Replaces the original with the patch's variant, as long as the patch sets every field of it
+    Replace,
+}
+
+impl OnMismatch {
+    pub(crate) fn parse(args: &mut Vec<TokenTree>) -> Option<Self> {
+        let lit = take_ident_literal("on_mismatch", args)?;
+        Some(match lit.to_string().trim_matches('"') {
+            "error" => Self::Error,
+            "replace" => Self::Replace,
+            v => abort!(lit, "Invalid value, expected `error` or `replace` but got `{}`", v),
         })
     }
 }
@@ -484,12 +1192,98 @@ pub(crate) struct ExtraConditions {
     #[cfg(feature = "openapi")]
     /// When true, the deriving struct has an `#[oai(example)]` attribute and the derived struct should handle this.
     pub has_oai_example: bool,
+    /// The fields of the deriving struct, or the variants when it's an enum
+    pub members: Vec<Member>,
+    /// The fields of each variant (in the same order as `members`) when it's an enum
+    pub variant_members: Vec<Vec<Member>>,
+    /// When true, the deriving type is an enum and `members` are its variants
+    pub is_enum: bool,
 }
 
 impl ExtraConditions {
-    pub(crate) fn parse(attr: &[Attribute]) -> Self {
+    pub(crate) fn parse(ast: &DeriveInput) -> Self {
+        let members = match &ast.data {
+            Data::Struct(data) => data.fields.iter().enumerate().map(|(i, f)| field_member(i, f)).collect(),
+            Data::Enum(data) => data.variants.iter().map(|v| Member::Named(v.ident.clone())).collect(),
+            Data::Union(_) => vec![],
+        };
+        let variant_members = match &ast.data {
+            Data::Enum(data) => data
+                .variants
+                .iter()
+                .map(|v| v.fields.iter().enumerate().map(|(i, f)| field_member(i, f)).collect())
+                .collect(),
+            _ => vec![],
+        };
+
         Self {
-            has_oai_example: has_oai_attribute(attr, Some("example")),
+            has_oai_example: has_oai_attribute(&ast.attrs, Some("example")),
+            members,
+            variant_members,
+            is_enum: matches!(ast.data, Data::Enum(_)),
+        }
+    }
+
+    /// Same as [`Self::validate_member`] but also for fields of enum variants, i.e. `Variant::field`
+    pub(crate) fn validate_path(&self, path: &FieldPath) {
+        let Some(variant) = &path.variant else {
+            return self.validate_member(&path.member);
+        };
+        if !self.is_enum {
+            abort!(variant, "Only fields of enum variants are named with `Variant::field`")
+        }
+        self.validate_member(&Member::Named(variant.clone()));
+
+        let index = self.members.iter().position(|m| *m == Member::Named(variant.clone())).unwrap();
+        let fields = &self.variant_members[index];
+        if fields.contains(&path.member) {
+            return;
+        }
+        let known: Vec<String> = fields.iter().map(member_name).collect();
+        let field = member_name(&path.member);
+        match did_you_mean(&field, &known) {
+            Some(suggestion) => abort!(path.member, "Unknown field `{}` of variant `{}`, did you mean `{}`?", field, variant, suggestion),
+            None => abort!(path.member, "Unknown field `{}` of variant `{}`, expected one of {:?}", field, variant, known),
+        }
+    }
+
+    /// Aborts when the member named in an argument doesn't exist on the deriving type, suggesting the closest match
+    pub(crate) fn validate_member(&self, member: &Member) {
+        if self.members.contains(member) {
+            return;
+        }
+        let kind = match self.is_enum {
+            true => "variant",
+            false => "field",
+        };
+
+        match member {
+            Member::Unnamed(index) if self.is_enum => abort!(
+                index.span,
+                "Enum variants are selected by their identifier, e.g. `fields(NotFound)`"
+            ),
+            Member::Unnamed(index) if self.members.iter().any(|m| matches!(m, Member::Named(_))) => abort!(
+                index.span,
+                "Only tuple struct fields can be selected by their position, use the field's identifier instead"
+            ),
+            Member::Unnamed(index) => abort!(
+                index.span,
+                "Unknown {} `{}`, the tuple struct only has {} fields",
+                kind,
+                index.index,
+                self.members.len()
+            ),
+            Member::Named(ident) if self.members.iter().any(|m| matches!(m, Member::Unnamed(_))) => abort!(
+                ident,
+                "Tuple struct fields are selected by their position, e.g. `fields(0, 2)`"
+            ),
+            Member::Named(ident) => {
+                let known: Vec<String> = self.members.iter().map(member_name).collect();
+                match did_you_mean(&ident.to_string(), &known) {
+                    Some(suggestion) => abort!(ident, "Unknown {} `{}`, did you mean `{}`?", kind, ident, suggestion),
+                    None => abort!(ident, "Unknown {} `{}`, expected one of {:?}", kind, ident, known),
+                }
+            }
         }
     }
 }
@@ -0,0 +1,111 @@
+use proc_macro2::Literal;
+use proc_macro_error::abort;
+
+/// The casing to rename all fields to, following serde's `rename_all` naming (minus those that can't be identifiers)
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+}
+
+impl RenameRule {
+    pub(crate) fn parse(lit: &Literal) -> Self {
+        let value = lit.to_string();
+        match value.trim_matches('"') {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            v => abort!(
+                lit,
+                "Invalid value, expected `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case` or `SCREAMING_SNAKE_CASE` but got `{}`", v
+            ),
+        }
+    }
+
+    pub(crate) fn apply(&self, name: &str) -> String {
+        let words = split_words(name);
+        match self {
+            Self::Lower => words.concat(),
+            Self::Upper => words.concat().to_uppercase(),
+            Self::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| match i {
+                    0 => w.to_string(),
+                    _ => capitalize(w),
+                })
+                .collect(),
+            Self::Snake => words.join("_"),
+            Self::ScreamingSnake => words.join("_").to_uppercase(),
+        }
+    }
+}
+
+/// Splits a name into its lowercase words, by underscores and lowercase to uppercase boundaries
+fn split_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    let mut previous_lower = false;
+
+    for c in name.chars() {
+        if c == '_' {
+            words.push(String::new());
+            previous_lower = false;
+            continue;
+        }
+        if words.is_empty() || (c.is_uppercase() && previous_lower) {
+            words.push(String::new());
+        }
+        words.last_mut().unwrap().extend(c.to_lowercase());
+        previous_lower = c.is_lowercase() || c.is_numeric();
+    }
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Renames a field by the rule of serde's `rename_all` (given as its string), as serde does for the keys of a struct.
+/// `None` when the rule isn't one of serde's.
+pub(crate) fn serde_rename_field(rule: &str, field: &str) -> Option<String> {
+    let pascal = || {
+        let mut capitalize = true;
+        field.chars().fold(String::new(), |mut pascal, c| {
+            match (c, capitalize) {
+                ('_', _) => capitalize = true,
+                (c, true) => {
+                    pascal.push(c.to_ascii_uppercase());
+                    capitalize = false;
+                }
+                (c, false) => pascal.push(c),
+            }
+            pascal
+        })
+    };
+    Some(match rule {
+        "lowercase" | "snake_case" => field.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            chars.next().map(|c| c.to_ascii_lowercase()).into_iter().chain(chars).collect()
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        _ => return None,
+    })
+}
@@ -0,0 +1,171 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::HashSet;
+use syn::{punctuated::Punctuated, GenericParam, Generics, Type, WhereClause, WherePredicate};
+
+/// The generics of the original type, split into the ones the generated model still uses and the ones it doesn't.
+#[derive(Clone)]
+pub(crate) struct ModelGenerics {
+    /// Generics of the original type, untouched
+    pub original: Generics,
+    /// Generics that are still referenced by the fields of the generated model
+    pub model: Generics,
+    /// Generics of the original type which the generated model no longer uses, i.e. they only appear in omitted fields.
+    /// These need to be declared on methods taking the original type as they can't live on the impl block.
+    pub pruned: Generics,
+}
+
+impl ModelGenerics {
+    /// Splits the original generics by what is referenced from the given field types of the generated model
+    pub(crate) fn new<'a>(generics: &Generics, types: impl IntoIterator<Item = &'a Type>) -> Self {
+        let mut used = HashSet::new();
+        types
+            .into_iter()
+            .for_each(|ty| collect_names(ty.to_token_stream(), &mut used));
+
+        // Params used in the bounds of a used param must also be kept, e.g. `T: Into<U>`
+        loop {
+            let before = used.len();
+            let bounds: Vec<_> = generics
+                .params
+                .iter()
+                .filter(|p| used.contains(&param_name(p)))
+                .map(param_bounds)
+                .collect();
+            bounds
+                .into_iter()
+                .for_each(|b| collect_names(b, &mut used));
+            if before == used.len() {
+                break;
+            }
+        }
+
+        let all: HashSet<String> = generics.params.iter().map(param_name).collect();
+        let (kept, pruned): (Vec<_>, Vec<_>) = generics
+            .params
+            .iter()
+            .cloned()
+            .partition(|p| used.contains(&param_name(p)));
+
+        // Only predicates that solely reference kept params can stay on the model
+        let (kept_predicates, pruned_predicates): (Vec<_>, Vec<_>) = generics
+            .where_clause
+            .iter()
+            .flat_map(|w| w.predicates.iter().cloned())
+            .partition(|p| {
+                let mut names = HashSet::new();
+                collect_names(p.to_token_stream(), &mut names);
+                names.intersection(&all).all(|n| used.contains(n))
+            });
+
+        // Methods declare the pruned params along with only the pruned where clause, so their bounds are moved into it
+        let (pruned, pruned_bounds): (Vec<_>, Vec<_>) = pruned.into_iter().map(take_bounds).unzip();
+        let pruned_predicates = pruned_bounds.into_iter().flatten().chain(pruned_predicates).collect();
+
+        Self {
+            original: generics.clone(),
+            model: build_generics(generics, kept, kept_predicates),
+            pruned: build_generics(generics, pruned, pruned_predicates),
+        }
+    }
+
+    /// Returns the where clause of the original type with the given extra predicates appended
+    pub(crate) fn original_where_with(&self, extra: Vec<WherePredicate>) -> Option<WhereClause> {
+        where_with(&self.original, extra)
+    }
+
+    /// Returns the where clause of the pruned params (i.e. for methods) with the given extra predicates appended
+    pub(crate) fn pruned_where_with(&self, extra: Vec<WherePredicate>) -> Option<WhereClause> {
+        where_with(&self.pruned, extra)
+    }
+}
+
+fn where_with(generics: &Generics, extra: Vec<WherePredicate>) -> Option<WhereClause> {
+    let mut where_clause = generics.where_clause.clone();
+    if !extra.is_empty() {
+        where_clause
+            .get_or_insert_with(|| syn::parse_quote!(where))
+            .predicates
+            .extend(extra);
+    }
+    where_clause
+}
+
+fn build_generics(
+    original: &Generics,
+    params: Vec<GenericParam>,
+    predicates: Vec<WherePredicate>,
+) -> Generics {
+    let params: Punctuated<GenericParam, syn::Token![,]> = params.into_iter().collect();
+    let where_clause = match predicates.is_empty() {
+        true => None,
+        false => Some(WhereClause {
+            where_token: Default::default(),
+            predicates: predicates.into_iter().collect(),
+        }),
+    };
+
+    Generics {
+        lt_token: (!params.is_empty()).then(|| original.lt_token.unwrap_or_default()),
+        gt_token: (!params.is_empty()).then(|| original.gt_token.unwrap_or_default()),
+        params,
+        where_clause,
+    }
+}
+
+/// Removes the inline bounds of the param, returning them as a where predicate
+fn take_bounds(mut param: GenericParam) -> (GenericParam, Option<WherePredicate>) {
+    let predicate = match &mut param {
+        GenericParam::Type(t) if !t.bounds.is_empty() => {
+            let (ident, bounds) = (&t.ident, std::mem::take(&mut t.bounds));
+            t.colon_token = None;
+            Some(syn::parse_quote!(#ident: #bounds))
+        }
+        GenericParam::Lifetime(l) if !l.bounds.is_empty() => {
+            let (lifetime, bounds) = (&l.lifetime, std::mem::take(&mut l.bounds));
+            l.colon_token = None;
+            Some(syn::parse_quote!(#lifetime: #bounds))
+        }
+        _ => None,
+    };
+    (param, predicate)
+}
+
+/// The name of the param as it would be referenced in a type, lifetimes include their leading `'`
+fn param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Type(t) => t.ident.to_string(),
+        GenericParam::Lifetime(l) => format!("'{}", l.lifetime.ident),
+        GenericParam::Const(c) => c.ident.to_string(),
+    }
+}
+
+fn param_bounds(param: &GenericParam) -> TokenStream {
+    match param {
+        GenericParam::Type(t) => t.bounds.to_token_stream(),
+        GenericParam::Lifetime(l) => l.bounds.to_token_stream(),
+        GenericParam::Const(c) => c.ty.to_token_stream(),
+    }
+}
+
+/// Collects every identifier and lifetime in the tokens. Lifetimes are collected with their leading `'`
+fn collect_names(tokens: TokenStream, names: &mut HashSet<String>) {
+    let mut is_lifetime = false;
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(g) => collect_names(g.stream(), names),
+            TokenTree::Ident(i) if is_lifetime => {
+                names.insert(format!("'{i}"));
+            }
+            TokenTree::Ident(i) => {
+                names.insert(i.to_string());
+            }
+            TokenTree::Punct(p) if p.as_char() == '\'' => {
+                is_lifetime = true;
+                continue;
+            }
+            _ => {}
+        }
+        is_lifetime = false;
+    }
+}
@@ -1,8 +1,9 @@
 use args::ModelAttrArgs;
+use generics::ModelGenerics;
 use proc_macro2::{Group, Ident, Literal, TokenStream, TokenTree};
 use proc_macro_error::abort;
-use quote::quote;
-use syn::{parse2, Attribute};
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, parse2, Attribute, Field, Fields, Member, WhereClause};
 
 #[cfg(test)]
 mod tests;
@@ -11,6 +12,8 @@ mod openapi;
 pub(crate) use openapi::*;
 
 pub(crate) mod args;
+pub(crate) mod case;
+pub(crate) mod generics;
 
 /// Check the first segment of an attribute to see if it matches the given name
 pub(crate) fn is_attribute(attr: &Attribute, name: &str) -> bool {
@@ -24,6 +27,41 @@ pub(crate) fn get_attribute_name(attr: &Attribute) -> Option<&Ident> {
 
 //  "Invalid or missing `{name}` argument, expected a group of args, e.g. `{name}(...)`"
 
+/// Extract a type for a given identifier, e.g. `name = path::to::Type<_>`. Commas inside angle brackets are part of the
+/// type, the first one outside of them ends it.
+pub(crate) fn take_ident_type(name: &str, args: &mut Vec<TokenTree>) -> Option<syn::Type> {
+    let i = args.iter().position(|tk| matches!(tk, TokenTree::Ident(v) if *v == name))?;
+    match args.get(i + 1) {
+        Some(TokenTree::Punct(p)) if p.as_char() == '=' => {}
+        _ => abort!(args[i], "Invalid or missing `{name}` argument, expected a type, e.g. `{name} = path::to::Type`"),
+    }
+
+    let mut depth = 0usize;
+    let end = args[i + 2..]
+        .iter()
+        .position(|tk| match tk {
+            TokenTree::Punct(p) if p.as_char() == '<' => {
+                depth += 1;
+                false
+            }
+            TokenTree::Punct(p) if p.as_char() == '>' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            TokenTree::Punct(p) => p.as_char() == ',' && depth == 0,
+            _ => false,
+        })
+        .map_or(args.len(), |n| i + 2 + n);
+    let tokens: TokenStream = args[i + 2..end].iter().cloned().collect();
+    let ty = match parse2::<syn::Type>(tokens) {
+        Ok(ty) => ty,
+        Err(_) => abort!(args[i], "Invalid `{name}` argument, expected a type, e.g. `{name} = path::to::Type`"),
+    };
+
+    args.drain(i..(end + 1).min(args.len())); // Including the trailing comma
+    Some(ty)
+}
+
 /// Extract a group for a given identifier, e.g. `name(...)`. The `(...)` part is returned.
 pub(crate) fn take_ident_group(name: &str, args: &mut Vec<TokenTree>) -> Option<Group> {
     for (i, tk) in args.iter().enumerate() {
@@ -83,37 +121,6 @@ pub(crate) fn take_ident_bool(name: &str, args: &mut Vec<TokenTree>) -> Option<b
     None
 }
 
-pub(crate) fn take_ident_ident(name: &str, args: &mut Vec<TokenTree>) -> Option<Ident> {
-    for (i, tk) in args.iter().enumerate() {
-        match tk {
-            TokenTree::Ident(v) if *v == name => match args.get(i + 1) {
-                Some(TokenTree::Punct(p)) if p.as_char() == '=' => {
-                    let value = match args
-                        .get(i + 2) {
-                            Some(proc_macro2::TokenTree::Ident(v))  => v.to_owned(),
-                            Some(v) => abort!(v, "Invalid arguement value, expected a identifier, e.g. `{} = *StructIdentifer*` but got {}", name, v),
-                            None => abort!(v, "Missing `{name}` argument, expected an identifier, e.g. `{} = MyStruct`", name),
-                        };
-                    
-                    args.remove(i); // Remove Ident {name}
-                    args.remove(i); // Remove Punct =
-                    args.remove(i); // Remove Ident {value}
-                    if matches!(args.get(i), Some(TokenTree::Punct(v)) if v.as_char() == ',') {
-                        args.remove(i); // Remove leading Comma
-                    }
-                    return Some(value);
-                }
-                _ => abort!(
-                    tk,
-                    "Invalid or missing `{name}` argument, expected an identifier, e.g. `{name} = MyStruct`"
-                ),
-            },
-            _ => {}
-        }
-    }
-    None
-}
-
 pub(crate) fn take_ident_literal(name: &str, args: &mut Vec<TokenTree>) -> Option<Literal> {
     for (i, tk) in args.iter().enumerate() {
         match tk {
@@ -195,7 +202,7 @@ pub(crate) fn take_path_group(name: &str, args: &mut Vec<TokenTree>) -> Option<V
     }
 }
 
-/// Parse the fields argument into a TokenStream, skipping checking for commas coz lazy
+/// Parse a group of identifiers, e.g. the model names in `skip(UserPublic, UserProfile)`
 pub(crate) fn extract_idents(group: Group) -> Vec<Ident> {
     group
         .stream()
@@ -203,11 +210,86 @@ pub(crate) fn extract_idents(group: Group) -> Vec<Ident> {
         .filter_map(|tt| match tt {
             TokenTree::Ident(ident) => Some(ident),
             TokenTree::Punct(v) if v.as_char() == ',' => None,
-            tt => abort!(tt, "Invalid syntax, expected a field identifier, got {tt}`"),
+            tt => abort!(tt, "Invalid syntax, expected an identifier, got {tt}`"),
         })
         .collect()
 }
 
+/// The name of a type's last path segment along with its type arguments, e.g. `("HashMap", [K, V])` for
+/// `std::collections::HashMap<K, V>`, to tell what kind of container it is
+pub(crate) fn container_of(ty: &syn::Type) -> Option<(String, Vec<&syn::Type>)> {
+    let segment = match ty {
+        syn::Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+        _ => return None,
+    };
+    let types = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                syn::GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+    Some((segment.ident.to_string(), types))
+}
+
+/// The member used to access a field, i.e. its identifier or its position when the struct is a tuple struct
+pub(crate) fn field_member(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(index.into()),
+    }
+}
+
+/// The name of a member, i.e. the identifier (without any `r#`) or the position for tuple struct fields
+pub(crate) fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.unraw().to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+/// Finds the closest candidate to the given name, if any are close enough to likely be a typo of it
+pub(crate) fn did_you_mean<'a>(name: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let max_distance = usize::max(1, name.chars().count() / 3);
+    candidates
+        .iter()
+        .map(|c| (edit_distance(name, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c.as_str())
+}
+
+/// The Levenshtein distance between two strings, i.e. the number of single character edits to turn `a` into `b`
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Generates the body of a struct definition, which for tuple structs needs the where clause after the fields
+pub(crate) fn gen_struct_body(
+    fields: &Fields,
+    field_tokens: &[TokenStream],
+    where_clause: Option<&WhereClause>,
+) -> TokenStream {
+    match fields {
+        Fields::Unnamed(_) => quote!((#(#field_tokens),*) #where_clause;),
+        Fields::Named(_) | Fields::Unit => quote!(#where_clause { #(#field_tokens),* }),
+    }
+}
 
 pub(crate) fn is_doc(v: &&Attribute) -> bool {
     v.meta.require_name_value().is_ok_and(|v| {
@@ -253,15 +335,75 @@ pub(crate) fn gen_derive(
     )
 }
 
+/// Whether the model derives any of the given traits (e.g. serde's `Serialize`), either through its `derive` argument
+/// or the derive attributes it carries over from the original
+pub(crate) fn derives_any(derive: Option<&Vec<syn::Path>>, attributes: &[&Attribute], traits: &[&str]) -> bool {
+    let carried = attributes
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .filter_map(|attr| attr.parse_args_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated).ok())
+        .flatten();
+    derive
+        .into_iter()
+        .flatten()
+        .cloned()
+        .chain(carried)
+        .any(|p| p.segments.last().is_some_and(|s| traits.iter().any(|t| s.ident == t)))
+}
+
 /// Generates extra nice to have implementations for the generated models
  // Dev Note: Okay for now in generic logic module, but future extras may need to be handled in patch/view modules.
  pub fn impl_extras(
     original_name: &Ident,
     name: &Ident,
+    generics: &ModelGenerics,
     model_args: &ModelAttrArgs,
 ) -> Vec<TokenStream> {
     vec![
         #[cfg(feature = "openapi")]
-        impl_oai_example(name, original_name, model_args)
+        impl_oai_example(name, original_name, generics, model_args)
     ]
-}
\ No newline at end of file
+}
+
+/// The name of the error returned when converting into an enum model fails
+pub(crate) fn try_from_error_name(name: &Ident) -> Ident {
+    format_ident!("{}TryFromError", name)
+}
+
+/// Generates the error of converting into an enum model (of the given `kind`), which gives back the value of the
+/// variant that isn't part of the model
+pub(crate) fn impl_try_from_error(original_name: &Ident, name: &Ident, generics: &ModelGenerics, kind: &str) -> (Ident, TokenStream) {
+    let (impl_generics, ty_generics, where_clause) = generics.original.split_for_impl();
+    let original_generics = &generics.original;
+    let error_name = try_from_error_name(name);
+    let error_name_str = error_name.to_string();
+    let error_doc = format!("Error of converting [`{original_name}`] into [`{name}`], when the value is of a variant that isn't part of the {kind}");
+    let error_display = format!("variant `{{}}` of `{original_name}` isn't part of the {kind} `{name}`");
+
+    let tokens = quote! {
+        #[doc = #error_doc]
+        pub struct #error_name #original_generics #where_clause {
+            /// The original value which couldn't be converted
+            pub value: #original_name #ty_generics,
+            /// The name of the original's variant which isn't part of the model
+            pub variant: &'static str,
+        }
+
+        impl #impl_generics ::core::fmt::Debug for #error_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(#error_name_str)
+                    .field("variant", &self.variant)
+                    .finish_non_exhaustive()
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #error_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, #error_display, self.variant)
+            }
+        }
+
+        impl #impl_generics ::std::error::Error for #error_name #ty_generics #where_clause {}
+    };
+    (error_name, tokens)
+}
@@ -1,19 +1,27 @@
 use syn::Attribute;
 use quote::quote;
 use proc_macro2::{Ident, TokenStream};
-use super::args::ModelAttrArgs;
+use super::{args::ModelAttrArgs, generics::ModelGenerics};
 
 /// Checks if the model should derive the `Example` utilising the parents impl
-pub(crate) fn impl_oai_example(name: &Ident, original_name: &Ident, model_args: &ModelAttrArgs) -> TokenStream {
-    match model_args.extras.has_oai_example {
-       true => quote!{
-           impl ::poem_openapi::types::Example for #name {
-               fn example() -> Self {
-                   #original_name::example().into()
-               }
-           }
-       },
-       false => quote!(),
+pub(crate) fn impl_oai_example(name: &Ident, original_name: &Ident, generics: &ModelGenerics, model_args: &ModelAttrArgs) -> TokenStream {
+    // Params only used by omitted fields can't be picked for the original's example, so there is nothing to delegate to
+    if !model_args.extras.has_oai_example || !generics.pruned.params.is_empty() {
+        return quote!();
+    }
+
+    let (impl_generics, ty_generics, _) = generics.original.split_for_impl();
+    let (_, model_ty_generics, _) = generics.model.split_for_impl();
+    let where_clause = generics.original_where_with(vec![
+        syn::parse_quote!(#original_name #ty_generics: ::poem_openapi::types::Example)
+    ]);
+
+    quote!{
+        impl #impl_generics ::poem_openapi::types::Example for #name #model_ty_generics #where_clause {
+            fn example() -> Self {
+                <#original_name #ty_generics as ::poem_openapi::types::Example>::example().into()
+            }
+        }
     }
 }
 
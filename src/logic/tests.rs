@@ -48,3 +48,41 @@ pub fn should_extract_derives() {
     );
     assert_eq!(derives.len(), 2);
 }
+
+#[test]
+pub fn should_measure_edit_distance() {
+    assert_eq!(edit_distance("password", "password"), 0);
+    assert_eq!(edit_distance("pasword", "password"), 1);
+    assert_eq!(edit_distance("display_nmae", "display_name"), 2);
+    assert_eq!(edit_distance("", "bio"), 3);
+}
+
+#[test]
+pub fn should_suggest_closest_name() {
+    let known = ["id", "display_name", "password"].map(String::from);
+    assert_eq!(did_you_mean("pasword", &known), Some("password"));
+    assert_eq!(did_you_mean("display_nam", &known), Some("display_name"));
+    assert_eq!(did_you_mean("email", &known), None);
+}
+
+#[test]
+pub fn should_apply_rename_rules() {
+    use case::RenameRule::*;
+    assert_eq!(Camel.apply("password_hash"), "passwordHash");
+    assert_eq!(Pascal.apply("password_hash"), "PasswordHash");
+    assert_eq!(Snake.apply("UserProfile"), "user_profile");
+    assert_eq!(ScreamingSnake.apply("createdAt"), "CREATED_AT");
+    assert_eq!(Lower.apply("NotFound"), "notfound");
+    assert_eq!(Upper.apply("bio"), "BIO");
+    assert_eq!(Snake.apply("address2_line"), "address2_line");
+}
+
+#[test]
+pub fn should_apply_serde_rename_rules() {
+    use case::serde_rename_field;
+    assert_eq!(serde_rename_field("camelCase", "time_zone").as_deref(), Some("timeZone"));
+    assert_eq!(serde_rename_field("PascalCase", "time_zone").as_deref(), Some("TimeZone"));
+    assert_eq!(serde_rename_field("lowercase", "time_zone").as_deref(), Some("time_zone"));
+    assert_eq!(serde_rename_field("SCREAMING-KEBAB-CASE", "time_zone").as_deref(), Some("TIME-ZONE"));
+    assert_eq!(serde_rename_field("Title Case", "time_zone"), None);
+}
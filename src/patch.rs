@@ -1,11 +1,13 @@
 use crate::logic::{
-    args::{AttrArgs, ModelAttrArgs, OptionType},
+    args::{AttrArgs, CollectionsArg, ModelAttrArgs, NestedArg, NestedNone, OnMismatch, OptionType},
+    case::{serde_rename_field, RenameRule},
+    generics::ModelGenerics,
     *,
 };
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::abort;
-use quote::quote;
-use syn::{Attribute, DeriveInput, Type};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{spanned::Spanned, Attribute, DataEnum, DataStruct, DeriveInput, Member, Type, WherePredicate};
 
 pub fn impl_patch_model(
     ast: &DeriveInput,
@@ -14,61 +16,248 @@ pub fn impl_patch_model(
 ) -> TokenStream {
     // Argument and Variable Initialization and Prep
     let (args, mut remainder) = AttrArgs::parse(attr, model_args, false);
+
+    let skip_serializing_double_option =
+        take_ident_bool("skip_serializing_double_option", &mut remainder).unwrap_or_default();
+    let option = OptionType::parse(&mut remainder).unwrap_or_else(|| args.preset.option());
+    let on_mismatch = OnMismatch::parse(&mut remainder);
+    let diff = take_ident_bool("diff", &mut remainder).unwrap_or_default();
+    let merge_tracked = take_ident_bool("merge_tracked", &mut remainder).unwrap_or_default();
+    let merge_patch = take_ident_bool("merge_patch", &mut remainder).unwrap_or_default();
+    let json_patch = take_ident_bool("json_patch", &mut remainder).unwrap_or_default();
+    let nested = NestedArg::parse(&mut remainder, model_args, "patch");
+    let nested_none = NestedNone::parse(&mut remainder);
+    let collections = CollectionsArg::parse(&mut remainder, model_args);
+
+    AttrArgs::abort_unexpected(
+        &remainder,
+        &["option", "on_mismatch", "diff", "merge_tracked", "merge_patch", "json_patch", "nested", "nested_none", "collections"],
+    );
+    if merge_patch && !cfg!(feature = "serde") {
+        abort!(attr, "`merge_patch` needs the `serde` feature of restructed")
+    }
+    if json_patch && !cfg!(feature = "serde") {
+        abort!(attr, "`json_patch` needs the `serde` feature of restructed")
+    }
+    if nested_none.is_some() && nested.is_empty() {
+        abort!(attr, "`nested_none` only applies to patches with `nested` fields")
+    } else if !nested.is_empty() && merge_tracked {
+        abort!(attr, "`merge_tracked` isn't supported on patches with `nested` fields")
+    } else if !nested.is_empty() && json_patch {
+        abort!(attr, "`json_patch` isn't supported on patches with `nested` fields")
+    }
+    // Operations other than `set` can't be expressed by a value, so they can't be compared, reported or converted
+    for (enabled, method) in [(merge_tracked, "merge_tracked"), (merge_patch, "merge_patch"), (json_patch, "json_patch")] {
+        if enabled && !collections.is_empty() {
+            abort!(attr, "`{}` isn't supported on patches with `collections` fields", method)
+        }
+    }
+
+    let attributes = args.attributes_with.gen_top_attributes(ast);
+    // The generated operations have no OpenAPI schema, so they can't be fields of an `Object`
+    if !collections.is_empty() && derives_any(args.derive.as_ref(), &attributes, &["Object"]) {
+        abort!(attr, "`collections` isn't supported on patches deriving `Object`, as their operations have no OpenAPI representation")
+    }
+    let deserialize = derives_any(args.derive.as_ref(), &attributes, &["Deserialize"]);
+    let (double_option, impl_double_option) = match (&option, deserialize && cfg!(feature = "serde")) {
+        (OptionType::Option, true) => {
+            let (ident, helper) = impl_deserialize_double_option(&args.name);
+            (Some(ident), helper)
+        }
+        _ => (None, quote!()),
+    };
+    let methods = PatchMethods { diff, merge_tracked, merge_patch, json_patch };
+    let options = PatchOptions {
+        option,
+        skip_serializing_double_option,
+        serde: deserialize || derives_any(args.derive.as_ref(), &attributes, &["Serialize"]),
+        double_option,
+        nested,
+        nested_none: nested_none.unwrap_or_default(),
+        collections,
+    };
+
+    let model = match &ast.data {
+        syn::Data::Struct(data) => {
+            if on_mismatch.is_some() {
+                abort!(attr, "`on_mismatch` only applies to patches of enums")
+            }
+            impl_for_struct(ast, data, &args, &options, methods, model_args)
+        }
+        syn::Data::Enum(_) if diff => abort!(attr, "`diff` is only supported on struct patches"),
+        syn::Data::Enum(_) if merge_tracked => abort!(attr, "`merge_tracked` is only supported on struct patches"),
+        syn::Data::Enum(_) if merge_patch => abort!(attr, "`merge_patch` is only supported on struct patches"),
+        syn::Data::Enum(_) if json_patch => abort!(attr, "`json_patch` is only supported on struct patches"),
+        syn::Data::Enum(data) => impl_for_enum(ast, attr, data, &args, &options, on_mismatch.unwrap_or_default(), model_args),
+        syn::Data::Union(_) => abort!(attr, "Patch Models can only be derived for `struct` & `enum`, NOT `union`"),
+    };
+    quote! {
+        #model
+        #impl_double_option
+    }
+}
+
+/// Generates the `deserialize_with` function of the patch's `Option<Option<T>>` fields, as serde would otherwise
+/// deserialize both a missing field and `null` to `None`. This way `null` becomes `Some(None)`, clearing the field.
+fn impl_deserialize_double_option(name: &Ident) -> (Ident, TokenStream) {
+    let ident = format_ident!("__{}_deserialize_double_option", name);
+    let helper = quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        fn #ident<'de, D, T>(deserializer: D) -> ::core::result::Result<::core::option::Option<::core::option::Option<T>>, D::Error>
+        where
+            D: ::serde::Deserializer<'de>,
+            T: ::serde::Deserialize<'de>,
+        {
+            <::core::option::Option<T> as ::serde::Deserialize>::deserialize(deserializer).map(::core::option::Option::Some)
+        }
+    };
+    (ident, helper)
+}
+
+/// Options shared by every field of the patch
+#[derive(Clone)]
+struct PatchOptions {
+    option: OptionType,
+    skip_serializing_double_option: bool,
+    /// Whether the patch derives serde's `Serialize` or `Deserialize`, so fields may need `#[serde(...)]` attributes
+    serde: bool,
+    /// The `deserialize_with` function of `Option<Option<T>>` fields, when the patch derives `Deserialize`
+    double_option: Option<Ident>,
+    /// Fields updated through their own patch model, only for structs
+    nested: Vec<NestedArg>,
+    nested_none: NestedNone,
+    /// Fields updated by operations instead of being replaced, only for structs
+    collections: CollectionsArg,
+}
+
+/// Opt-in methods of struct patches, as they need bounds on the types of the fields
+#[derive(Clone, Copy)]
+struct PatchMethods {
+    diff: bool,
+    merge_tracked: bool,
+    merge_patch: bool,
+    json_patch: bool,
+}
+
+fn impl_for_struct(
+    ast: &DeriveInput,
+    data: &DataStruct,
+    args: &AttrArgs,
+    options: &PatchOptions,
+    methods: PatchMethods,
+    model_args: &ModelAttrArgs,
+) -> TokenStream {
     let AttrArgs {
         name,
         fields: _,
         derive,
         preset,
         attributes_with,
-    } = args.clone();
-
-    let skip_serializing_double_option =
-        take_ident_bool("skip_serializing_double_option", &mut remainder).unwrap_or_default();
-    let option = OptionType::parse(&mut remainder).unwrap_or_else(|| preset.option());
-
-    AttrArgs::abort_unexpected(&remainder, &["option"]);
-
+        rename,
+    } = args;
     let original_name = &ast.ident;
 
     // Build the fields for the new type, wrapping each original field in an Option
-    let mut fields_and_is_option: Vec<(&Ident, bool)> = vec![];
+    let mut patch_fields: Vec<PatchField> = vec![];
+    let mut field_types: Vec<&Type> = vec![];
+    let mut model_types: Vec<&Type> = vec![];
     let mut fields: Vec<_> = vec![];
-    match &ast.data {
-        syn::Data::Struct(data) => data
-            .fields
-            .iter()
-            .filter(|f| {
-                preset.predicate(f)
-                    && args
-                        .fields
-                        .predicate(f.ident.as_ref().expect("Field must be named"))
-            })
-            .for_each(|field| {
-                let field_name = &field.ident.as_ref().unwrap();
-
-                // Add
-                let docs = extract_docs(&field.attrs);
-                let field_ty = &field.ty;
-                let option_ty = extract_type_from_option(field_ty);
-
-                fields_and_is_option.push((field_name, option_ty.is_some()));
-                fields.push(impl_struct_fields(
-                    field_name,
-                    field_ty,
-                    option_ty,
-                    &docs,
-                    option,
-                    skip_serializing_double_option,
-                ));
-            }),
-        _ => abort!(attr, "Patch Models can only be derived for structs"),
-    };
-
+    let selected: Vec<_> = data
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(i, f)| preset.predicate(f) && args.includes(&field_member(*i, f), &f.attrs, "patch"))
+        .collect();
+    let members: Vec<_> = selected.iter().map(|(i, f)| field_member(*i, f)).collect();
+    rename.validate(&members);
+    NestedArg::validate(&options.nested, &members, "patch");
+    options.collections.validate(&members);
     let attributes = attributes_with.gen_top_attributes(ast);
+    let mut operation_types: Vec<TokenStream> = vec![];
+    let mut operation_bounds: Vec<WherePredicate> = vec![];
+
+    selected
+        .into_iter()
+        .enumerate()
+        .for_each(|(patch_index, (i, field))| {
+            let renamed = field.ident.as_ref().map(|v| rename.rename(v));
+            let original = field_member(i, field);
+            let nested = NestedArg::find(&options.nested, &original).map(|n| &n.model);
+            let mut patch_field = PatchField::new(
+                match &renamed {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(patch_index.into()), // Tuple structs are renumbered by what is left
+                },
+                original,
+                &field.ty,
+                nested.cloned(),
+                &options.option,
+            );
+            if options.collections.contains(&patch_field.original) {
+                if nested.is_some() {
+                    abort!(field.ty, "Field `{}` can't be both nested and a collection", member_name(&patch_field.original))
+                }
+                let (ty, bounds, tokens) = impl_collection_operation(ast, name, &patch_field.original, &field.ty, derive.as_ref(), &attributes);
+                patch_field.operation = Some(ty);
+                operation_bounds.extend(bounds);
+                operation_types.push(tokens);
+            }
+            field_types.push(&field.ty);
+            model_types.push(nested.unwrap_or(&field.ty));
+            fields.push(patch_field.gen_definition(&quote!(pub), renamed.as_ref(), field, options));
+            patch_fields.push(patch_field);
+        });
+
+    let generics = ModelGenerics::new(&ast.generics, model_types);
+    let (impl_generics, ty_generics, where_clause) = generics.original.split_for_impl();
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let (pruned_impl_generics, _, pruned_where_clause) = generics.pruned.split_for_impl();
+    let model_generics = &generics.model;
+
     let derives = gen_derive(derive.as_ref());
-    let impl_from_derived = impl_from_derived(&fields_and_is_option, option);
-    let impl_merge = impl_merge(&fields_and_is_option, option);
-    let impl_extras = impl_extras(original_name, &name, model_args);
+    let allow_lints = rename.gen_allow_lints();
+    let body = gen_struct_body(&data.fields, &fields, model_where_clause);
+    let impl_from_derived = patch_fields.iter().map(|f| {
+        let (member, original) = (&f.member, &f.original);
+        let value = f.gen_from_original(f.gen_into_patch(quote!(value.#original)));
+        quote!(#member: #value,)
+    });
+    let (impl_merge, merge_error) = impl_struct_merge(original_name, name, &patch_fields, options);
+    let (merge_output, merge_mut_output, merge_ok, merge_mut_ok, merge_try) = match &merge_error {
+        Some((error_name, _)) => (
+            quote!(::core::result::Result<#original_name #ty_generics, #error_name>),
+            quote!(-> ::core::result::Result<(), #error_name>),
+            quote!(::core::result::Result::Ok(value)),
+            quote!(::core::result::Result::Ok(())),
+            quote!(?),
+        ),
+        None => (quote!(#original_name #ty_generics), quote!(), quote!(value), quote!(), quote!()),
+    };
+    let impl_merge_error = merge_error.map(|(_, error)| error);
+    // Applying the operations of collections needs their elements to be comparable
+    let merge_where_clause = generics.pruned_where_with(operation_bounds);
+    let impl_extras = impl_extras(original_name, name, &generics, model_args);
+    let impl_introspection = impl_introspection(name, &generics, &patch_fields);
+    let impl_diff = match methods.diff {
+        true => impl_diff(original_name, name, &generics, &patch_fields, &field_types),
+        false => quote!(),
+    };
+    let keys = serde_keys(&patch_fields, &attributes);
+    let impl_merge_patch = match (methods.merge_patch, &data.fields) {
+        (true, syn::Fields::Named(_)) => impl_merge_patch(name, &generics, &patch_fields, &field_types, &keys),
+        (true, _) => abort!(name, "`merge_patch` needs named fields, as they're the keys of the document"),
+        (false, _) => quote!(),
+    };
+    let impl_json_patch = match (methods.json_patch, &data.fields) {
+        (true, syn::Fields::Named(_)) => impl_json_patch(original_name, name, &generics, &patch_fields, &field_types, &keys),
+        (true, _) => abort!(name, "`json_patch` needs named fields, as they're the paths of the operations"),
+        (false, _) => quote!(),
+    };
+    let impl_merge_tracked = match methods.merge_tracked {
+        true => impl_merge_tracked(original_name, name, &generics, &patch_fields, &field_types),
+        false => quote!(),
+    };
 
     // Generate the implementation of the PatchModel trait
     quote! {
@@ -76,155 +265,1347 @@ pub fn impl_patch_model(
         /// Generated patch model of [`#original_name`]
         #derives
         #(#attributes)*
-        pub struct #name {
-            #(#fields),*
-        }
+        #allow_lints
+        pub struct #name #model_generics #body
 
-        impl #name  {
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
             /// This is what the [`From`] trait calls internally to map between the original and generated type.
-            pub fn from_derived(value: #original_name) -> Self {
+            pub fn from_derived #pruned_impl_generics (value: #original_name #ty_generics) -> Self #pruned_where_clause {
                 Self {
-                    #impl_from_derived
+                    #(#impl_from_derived)*
                 }
             }
 
             /// Merges the updates into the given value, returning the updated value <br/>
             /// The only fields to change will be the ones that are Some. <br/>
             /// if your using the openapi feature then then only [`MaybeUndefined::Undefined`] are ingored
-            pub fn merge(self, mut value: #original_name) -> #original_name {
-                self.merge_mut(&mut value);
-                value
+            pub fn merge #pruned_impl_generics (self, mut value: #original_name #ty_generics) -> #merge_output #merge_where_clause {
+                self.merge_mut(&mut value)#merge_try;
+                #merge_ok
             }
 
             /// Mutable reference version of [`Self::merge`]
-            pub fn merge_mut(self, mut value: &mut #original_name) {
+            pub fn merge_mut #pruned_impl_generics (self, mut value: &mut #original_name #ty_generics) #merge_mut_output #merge_where_clause {
                 #impl_merge
+                #merge_mut_ok
             }
         }
 
 
-        impl ::core::convert::From<#original_name> for #name  {
-            fn from(value: #original_name) -> Self {
-                #name::from_derived(value)
+        impl #impl_generics ::core::convert::From<#original_name #ty_generics> for #name #model_ty_generics #where_clause {
+            fn from(value: #original_name #ty_generics) -> Self {
+                Self::from_derived(value)
             }
         }
 
+        #impl_merge_error
+        #(#operation_types)*
+        #impl_introspection
+        #impl_diff
+        #impl_merge_tracked
+        #impl_merge_patch
+        #impl_json_patch
         #(#impl_extras)*
     }
 }
 
-fn impl_merge(fields: &[(&Ident, bool)], option: OptionType) -> TokenStream {
-    match option {
-        OptionType::MaybeUndefined => {
-            let option_field: Vec<_> = fields
-                .iter()
-                .filter_map(|(ident, is_option)| is_option.then_some(ident))
-                .collect();
+/// Generates the body of `merge_mut` for struct patches, along with the name and definition of the `{Name}MergeError`
+/// it returns when nested patches can't be merged into `None` (with `nested_none = "error"`)
+fn impl_struct_merge(
+    original_name: &Ident,
+    name: &Ident,
+    patch_fields: &[PatchField],
+    options: &PatchOptions,
+) -> (TokenStream, Option<(Ident, TokenStream)>) {
+    let nested_options: Vec<&PatchField> = patch_fields.iter().filter(|f| f.nested.is_some() && f.is_option).collect();
+    let error_name = match options.nested_none == NestedNone::Error && !nested_options.is_empty() {
+        true => Some(format_ident!("{}MergeError", name)),
+        false => None,
+    };
+
+    let is_fallible = error_name.is_some();
+    let merges = patch_fields.iter().map(|f| {
+        let (member, original) = (&f.member, &f.original);
+        match (&f.nested, is_fallible) {
+            (None, _) if f.operation.is_some() => quote! {
+                for operation in self.#member {
+                    operation.apply(&mut value.#original);
+                }
+            },
+            (None, _) => f.gen_merge(quote!(self.#member), quote!(value.#original)),
+            // `None` was already checked for before merging anything
+            (Some(_), true) => f.gen_nested_merge(quote!(self.#member), quote!(value.#original), quote!({})),
+            (Some(_), false) => {
+                let finish = f.gen_nested_finish();
+                let initialized = quote!({
+                    let mut o = ::core::default::Default::default();
+                    #finish(p.merge_mut(&mut o));
+                    value.#original = ::core::option::Option::Some(o);
+                });
+                f.gen_nested_merge(quote!(self.#member), quote!(value.#original), initialized)
+            }
+        }
+    });
+
+    let Some(error_name) = error_name else {
+        return (quote!(#(#merges)*), None);
+    };
+    let checks = nested_options.iter().map(|f| {
+        let (member, original) = (&f.member, &f.original);
+        let is_value = f.gen_is_value(quote!(self.#member));
+        let field = member_name(original);
+        quote! {
+            if #is_value && value.#original.is_none() {
+                return ::core::result::Result::Err(#error_name { field: #field });
+            }
+        }
+    });
+
+    let error_doc = format!("Error of merging a [`{name}`] into a [`{original_name}`] whose field is `None`, which nested patches can't be merged into");
+    let merge = quote! {
+        #(#checks)*
+        #(#merges)*
+    };
+    let error = quote! {
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct #error_name {
+            /// The name of the original's field
+            pub field: &'static str,
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "can't merge the nested patch of `{}` as the field is `None`", self.field)
+            }
+        }
 
-            let required_field: Vec<_> = fields
-                .iter()
-                .filter_map(|(ident, is_option)| (!is_option).then_some(ident))
-                .collect();
+        impl ::std::error::Error for #error_name {}
+    };
+    (merge, Some((error_name, error)))
+}
 
+/// Generates the `{Name}{Field}Op` of a collection field, i.e. the operation merging the patch applies to it instead
+/// of replacing the whole collection. Returns along with it the type of the operation (with the generics it uses) and
+/// the bounds applying it needs.
+fn impl_collection_operation(
+    ast: &DeriveInput,
+    name: &Ident,
+    field: &Member,
+    ty: &Type,
+    derive: Option<&Vec<syn::Path>>,
+    attributes: &[&Attribute],
+) -> (Type, Vec<WherePredicate>, TokenStream) {
+    let (container, types) = container_of(ty).unwrap_or_default();
+    // Elements are removed and retained by the ones given, keys of maps are removed by themselves
+    let (variants, arms) = match (container.as_str(), types.as_slice()) {
+        ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [_, ..]) => (
             quote! {
-                #(
-                    match self.#required_field {
-                        ::core::option::Option::Some(v) => value.#required_field = v,
-                        ::core::option::Option::None => {},
+                /// Adds the elements
+                Append(#ty),
+                /// Removes the elements equal to any of the given ones
+                Remove(#ty),
+                /// Keeps only the elements equal to any of the given ones
+                Retain(#ty),
+                /// Removes every element
+                Clear,
+                /// Replaces the whole collection
+                Set(#ty),
+            },
+            quote! {
+                Self::Append(v) => collection.extend(v),
+                Self::Remove(v) => collection.retain(|e| !v.contains(e)),
+                Self::Retain(v) => collection.retain(|e| v.contains(e)),
+                Self::Clear => collection.clear(),
+                Self::Set(v) => *collection = v,
+            },
+        ),
+        ("HashMap" | "BTreeMap", [key, _, ..]) => (
+            quote! {
+                /// Inserts the entries, replacing the values of keys already present
+                Insert(#ty),
+                /// Removes the entries of the keys
+                Remove(::std::vec::Vec<#key>),
+                /// Removes every entry
+                Clear,
+                /// Replaces the whole map
+                Set(#ty),
+            },
+            quote! {
+                Self::Insert(v) => collection.extend(v),
+                Self::Remove(keys) => keys.iter().for_each(|k| {
+                    collection.remove(k);
+                }),
+                Self::Clear => collection.clear(),
+                Self::Set(v) => *collection = v,
+            },
+        ),
+        _ => abort!(ty, "Collection fields must be a `Vec`, `VecDeque`, `HashSet`, `BTreeSet`, `HashMap` or `BTreeMap`"),
+    };
+    // What comparing the elements (or keys) takes in each collection, along with the hasher of hashed ones
+    let bounds: Vec<WherePredicate> = match (container.as_str(), types.as_slice()) {
+        ("Vec" | "VecDeque", [element, ..]) => vec![syn::parse_quote!(#element: ::core::cmp::PartialEq)],
+        ("BTreeSet" | "BTreeMap", [element, ..]) => vec![syn::parse_quote!(#element: ::core::cmp::Ord)],
+        ("HashSet", [element, hasher @ ..]) | ("HashMap", [element, _, hasher @ ..]) => {
+            let hasher = hasher.iter().map(|h| syn::parse_quote!(#h: ::core::hash::BuildHasher));
+            [syn::parse_quote!(#element: ::core::cmp::Eq + ::core::hash::Hash)].into_iter().chain(hasher).collect()
+        }
+        _ => vec![],
+    };
+
+    let generics = ModelGenerics::new(&ast.generics, [ty]);
+    let (impl_generics, ty_generics, where_clause) = generics.model.split_for_impl();
+    let operation_generics = &generics.model;
+    let operation_name = format_ident!("{}{}Op", name, RenameRule::Pascal.apply(&member_name(field)));
+
+    // The operation has to derive what the patch does for the patch's derives to hold
+    let derives: Vec<TokenStream> = [
+        ("Debug", quote!(::core::fmt::Debug)),
+        ("Clone", quote!(::core::clone::Clone)),
+        ("PartialEq", quote!(::core::cmp::PartialEq)),
+        ("Eq", quote!(::core::cmp::Eq)),
+        ("Serialize", quote!(::serde::Serialize)),
+        ("Deserialize", quote!(::serde::Deserialize)),
+    ]
+    .into_iter()
+    .filter(|(t, _)| derives_any(derive, attributes, &[t]))
+    .map(|(_, path)| path)
+    .collect();
+    let serde = match derives_any(derive, attributes, &["Serialize", "Deserialize"]) {
+        true => quote!(#[serde(rename_all = "snake_case")]),
+        false => quote!(),
+    };
+
+    let doc = format!("Operation of [`{name}`] on `{}`, applied to it on merge instead of replacing the whole collection", member_name(field));
+    let tokens = quote! {
+        #[doc = #doc]
+        #[derive(#(#derives),*)]
+        #serde
+        pub enum #operation_name #operation_generics #where_clause {
+            #variants
+        }
+
+        impl #impl_generics #operation_name #ty_generics #where_clause {
+            /// Applies the operation to the collection
+            pub fn apply(self, collection: &mut #ty)
+            where
+                #(#bounds,)*
+            {
+                match self {
+                    #arms
+                }
+            }
+        }
+    };
+    (syn::parse_quote!(#operation_name #ty_generics), bounds, tokens)
+}
+
+/// Generates the opt-in `diff(&old, &new)` of struct patches, setting only the fields that differ so merging the
+/// patch into `old` gives `new` (for the fields the patch holds)
+fn impl_diff(
+    original_name: &Ident,
+    name: &Ident,
+    generics: &ModelGenerics,
+    patch_fields: &[PatchField],
+    field_types: &[&Type],
+) -> TokenStream {
+    let (_, ty_generics, _) = generics.original.split_for_impl();
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let (pruned_impl_generics, _, _) = generics.pruned.split_for_impl();
+    let bounds = field_types
+        .iter()
+        .map(|ty| syn::parse_quote!(#ty: ::core::cmp::PartialEq + ::core::clone::Clone))
+        .collect();
+    let pruned_where_clause = generics.pruned_where_with(bounds);
+
+    let fields = patch_fields.iter().map(|f| {
+        let (member, original) = (&f.member, &f.original);
+        let changed = f.gen_from_changed(f.gen_into_patch(quote!(new.#original.clone())));
+        let unchanged = f.gen_undefined();
+        quote! {
+            #member: match old.#original == new.#original {
+                true => #unchanged,
+                false => #changed,
+            },
+        }
+    });
+
+    quote! {
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            /// A patch setting only the fields that differ between `old` and `new`, so merging it into `old` gives `new`
+            pub fn diff #pruned_impl_generics (old: &#original_name #ty_generics, new: &#original_name #ty_generics) -> Self #pruned_where_clause {
+                Self {
+                    #(#fields)*
+                }
+            }
+        }
+    }
+}
+
+/// Generates the opt-in `merge_tracked` of struct patches along with the `{Name}Change` it reports each field that
+/// actually changed with
+fn impl_merge_tracked(
+    original_name: &Ident,
+    name: &Ident,
+    generics: &ModelGenerics,
+    patch_fields: &[PatchField],
+    field_types: &[&Type],
+) -> TokenStream {
+    let (_, ty_generics, _) = generics.original.split_for_impl();
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let (pruned_impl_generics, _, _) = generics.pruned.split_for_impl();
+    let bounds = field_types
+        .iter()
+        .map(|ty| syn::parse_quote!(#ty: ::core::cmp::PartialEq + ::core::fmt::Debug))
+        .collect();
+    let pruned_where_clause = generics.pruned_where_with(bounds);
+    let change_name = format_ident!("{}Change", name);
+
+    let fields = patch_fields.iter().map(|f| {
+        let (member, original) = (&f.member, &f.original);
+        let replacement = f.gen_replacement(quote!(self.#member));
+        let field = member_name(original);
+        quote! {
+            if let ::core::option::Option::Some(v) = #replacement {
+                if value.#original != v {
+                    changes.push(#change_name {
+                        field: #field,
+                        old: ::std::format!("{:?}", value.#original),
+                        new: ::std::format!("{:?}", v),
+                    });
+                    value.#original = v;
+                }
+            }
+        }
+    });
+
+    let change_doc = format!("A field of [`{original_name}`] changed by [`{name}::merge_tracked`], with its values formatted by `Debug`");
+    quote! {
+        #[doc = #change_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct #change_name {
+            /// The name of the field on the original
+            pub field: &'static str,
+            /// The value before merging
+            pub old: ::std::string::String,
+            /// The value after merging
+            pub new: ::std::string::String,
+        }
+
+        impl ::core::fmt::Display for #change_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "`{}` changed from {} to {}", self.field, self.old, self.new)
+            }
+        }
+
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            /// Same as [`Self::merge_mut`], but reports each field whose value actually changed
+            pub fn merge_tracked #pruned_impl_generics (self, value: &mut #original_name #ty_generics) -> ::std::vec::Vec<#change_name> #pruned_where_clause {
+                let mut changes = ::std::vec::Vec::new();
+                #(#fields)*
+                changes
+            }
+        }
+    }
+}
+
+/// The keys of the patch's fields in JSON documents, i.e. their names on the patch renamed by the
+/// `#[serde(rename_all = "...")]` it carries with `attributes_with`, so they're the same as the patch's own serde names
+fn serde_keys(patch_fields: &[PatchField], attributes: &[&Attribute]) -> Vec<String> {
+    let mut rule: Option<String> = None;
+    for attr in attributes.iter().filter(|attr| attr.path().is_ident("serde")) {
+        // Anything serde itself would reject is left for it to report
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") && meta.input.peek(syn::Token![=]) {
+                rule = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("rename_all") {
+                // Documents are read more than written, so `deserialize` wins over `serialize`
+                meta.parse_nested_meta(|inner| {
+                    let value = inner.value()?.parse::<syn::LitStr>()?.value();
+                    if inner.path.is_ident("deserialize") || rule.is_none() {
+                        rule = Some(value);
                     }
-                )*
-                #(
-                    match self.#option_field {
-                        ::poem_openapi::types::MaybeUndefined::Value(v) => value.#option_field = ::core::option::Option::Some(v),
-                        ::poem_openapi::types::MaybeUndefined::Null => value.#option_field = ::core::option::Option::None,
-                        ::poem_openapi::types::MaybeUndefined::Undefined => {},
+                    Ok(())
+                })?;
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.input.parse::<proc_macro2::Group>()?;
+            }
+            Ok(())
+        });
+    }
+
+    patch_fields
+        .iter()
+        .map(|f| {
+            let name = member_name(&f.member);
+            rule.as_deref().and_then(|rule| serde_rename_field(rule, &name)).unwrap_or(name)
+        })
+        .collect()
+}
+
+/// Generates the opt-in conversions between struct patches and RFC 7386 JSON Merge Patch documents, where a missing
+/// key leaves the field unchanged and `null` clears it, along with the `{Name}MergePatchError` of invalid documents
+fn impl_merge_patch(
+    name: &Ident,
+    generics: &ModelGenerics,
+    patch_fields: &[PatchField],
+    field_types: &[&Type],
+    keys: &[String],
+) -> TokenStream {
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    // Nested patches bring their own bounds with their `from_merge_patch` and `to_merge_patch`
+    let bounds = field_types
+        .iter()
+        .zip(patch_fields)
+        .filter(|(_, f)| f.nested.is_none())
+        .map(|(ty, _)| quote!(#ty: ::serde::de::DeserializeOwned + ::serde::Serialize))
+        .collect::<Vec<_>>();
+    let error_name = format_ident!("{}MergePatchError", name);
+
+    let from_fields = patch_fields.iter().zip(field_types).zip(keys).map(|((f, ty), key)| {
+        let member = &f.member;
+        let undefined = f.gen_undefined();
+        let value = f.gen_from_json(quote!(v), ty, key, &error_name);
+        quote! {
+            #member: match object.get(#key) {
+                ::core::option::Option::None => #undefined,
+                ::core::option::Option::Some(v) => #value,
+            },
+        }
+    });
+    let to_fields = patch_fields.iter().zip(keys).map(|(f, key)| {
+        let member = &f.member;
+        let insert = |value: TokenStream| quote!(object.insert(#key.to_string(), #value));
+        let value = match &f.nested {
+            Some(_) => insert(quote!(v.to_merge_patch()?)),
+            None => insert(quote!(::serde_json::to_value(v)?)),
+        };
+        let null = insert(quote!(::serde_json::Value::Null));
+        match (&f.option, f.is_option) {
+            (OptionType::MaybeUndefined, true) => quote! {
+                match &self.#member {
+                    ::poem_openapi::types::MaybeUndefined::Value(v) => { #value; }
+                    ::poem_openapi::types::MaybeUndefined::Null => { #null; }
+                    ::poem_openapi::types::MaybeUndefined::Undefined => {}
+                }
+            },
+            (OptionType::Custom(_), true) => quote! {
+                match ::restructed_types::PatchOption::as_maybe(&self.#member) {
+                    ::restructed_types::Maybe::Value(v) => { #value; }
+                    ::restructed_types::Maybe::Null => { #null; }
+                    ::restructed_types::Maybe::Undefined => {}
+                }
+            },
+            (OptionType::Option, true) => quote! {
+                match &self.#member {
+                    ::core::option::Option::Some(::core::option::Option::Some(v)) => { #value; }
+                    ::core::option::Option::Some(::core::option::Option::None) => { #null; }
+                    ::core::option::Option::None => {}
+                }
+            },
+            (_, false) => quote! {
+                if let ::core::option::Option::Some(v) = &self.#member {
+                    #value;
+                }
+            },
+        }
+    });
+
+    let error_doc = format!("The error returned when a JSON Merge Patch document can't be turned into a [`{name}`]");
+    quote! {
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum #error_name {
+            /// The document isn't a JSON object
+            NotAnObject,
+            /// The document has a key that isn't a field of the patch
+            UnknownField(::std::string::String),
+            /// The document clears a field that isn't an `Option`
+            NullNotAllowed(&'static str),
+            /// The value of a field failed to deserialize
+            InvalidValue {
+                /// The field the value is for
+                field: &'static str,
+                /// Why the value failed to deserialize
+                message: ::std::string::String,
+            },
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    Self::NotAnObject => write!(f, "merge patch document must be a JSON object"),
+                    Self::UnknownField(key) => write!(f, "unknown field `{}`", key),
+                    Self::NullNotAllowed(field) => write!(f, "field `{}` can't be null", field),
+                    Self::InvalidValue { field, message } => write!(f, "invalid value for field `{}`: {}", field, message),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            /// Builds the patch from an RFC 7386 JSON Merge Patch document, rejecting keys that aren't fields of the
+            /// patch and `null` on fields that aren't an `Option`
+            pub fn from_merge_patch(document: &::serde_json::Value) -> ::core::result::Result<Self, #error_name>
+            where
+                #(#bounds,)*
+            {
+                let object = document.as_object().ok_or(#error_name::NotAnObject)?;
+                if let ::core::option::Option::Some(key) = object.keys().find(|k| ![#(#keys),*].contains(&k.as_str())) {
+                    return ::core::result::Result::Err(#error_name::UnknownField(key.clone()));
+                }
+                ::core::result::Result::Ok(Self {
+                    #(#from_fields)*
+                })
+            }
+
+            /// The RFC 7386 JSON Merge Patch document of the patch, holding only the fields it sets
+            pub fn to_merge_patch(&self) -> ::core::result::Result<::serde_json::Value, ::serde_json::Error>
+            where
+                #(#bounds,)*
+            {
+                let mut object = ::serde_json::Map::new();
+                #(#to_fields)*
+                ::core::result::Result::Ok(::serde_json::Value::Object(object))
+            }
+        }
+    }
+}
+
+/// Generates the opt-in `from_json_patch` of struct patches, translating RFC 6902 JSON Patch operations on the
+/// top-level fields into the patch, along with the `{Name}JsonPatch` holding it and the values `test` operations expect
+fn impl_json_patch(
+    original_name: &Ident,
+    name: &Ident,
+    generics: &ModelGenerics,
+    patch_fields: &[PatchField],
+    field_types: &[&Type],
+    keys: &[String],
+) -> TokenStream {
+    let (_, ty_generics, _) = generics.original.split_for_impl();
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let (pruned_impl_generics, _, _) = generics.pruned.split_for_impl();
+    let model_generics = &generics.model;
+    let bounds = field_types
+        .iter()
+        .map(|ty| quote!(#ty: ::serde::de::DeserializeOwned))
+        .collect::<Vec<_>>();
+    // Any field may be tested, so every one needs `PartialEq`, spanned so a missing one is reported on its field
+    let merge_bounds = field_types
+        .iter()
+        .map(|ty| syn::parse_quote_spanned!(ty.span()=> #ty: ::core::cmp::PartialEq))
+        .collect();
+    let pruned_where_clause = generics.pruned_where_with(merge_bounds);
+    let wrapper_name = format_ident!("{}JsonPatch", name);
+    let error_name = format_ident!("{}JsonPatchError", name);
+
+    let arms = patch_fields.iter().zip(field_types).zip(keys).map(|((f, ty), key)| {
+        let member = &f.member;
+        let value = f.gen_from_json(quote!(v), ty, key, &error_name);
+        let removed = match f.is_option {
+            true => {
+                let cleared = f.gen_from_changed(quote!(::core::option::Option::None));
+                quote!(target.#member = #cleared)
+            }
+            false => quote!(return ::core::result::Result::Err(#error_name::RemoveNotAllowed(#key))),
+        };
+        quote! {
+            (#key, ::core::option::Option::Some(v)) => target.#member = #value,
+            (#key, ::core::option::Option::None) => #removed,
+        }
+    });
+    let tests = patch_fields.iter().zip(keys).map(|(f, key)| {
+        let (member, original) = (&f.member, &f.original);
+        let expected = f.gen_replacement(quote!(test.#member));
+        quote! {
+            if let ::core::option::Option::Some(v) = #expected {
+                if value.#original != v {
+                    return ::core::result::Result::Err(#error_name::TestFailed(#key));
+                }
+            }
+        }
+    });
+
+    let wrapper_doc = format!("A [`{name}`] built from JSON Patch operations, along with the values its `test` operations expect");
+    let error_doc = format!("The error of turning JSON Patch operations into a [`{name}`] or merging it into a [`{original_name}`]");
+    quote! {
+        #[doc = #wrapper_doc]
+        pub struct #wrapper_name #model_generics #model_where_clause {
+            /// The fields set by `add`, `replace` and `remove` operations
+            pub patch: #name #model_ty_generics,
+            /// The fields checked by `test` operations, holding the values they expect
+            pub test: #name #model_ty_generics,
+        }
+
+        impl #model_impl_generics #wrapper_name #model_ty_generics #model_where_clause {
+            /// Merges the patch into the given value when every `test` operation holds, returning the updated value
+            pub fn merge #pruned_impl_generics (self, mut value: #original_name #ty_generics) -> ::core::result::Result<#original_name #ty_generics, #error_name> #pruned_where_clause {
+                self.merge_mut(&mut value)?;
+                ::core::result::Result::Ok(value)
+            }
+
+            /// Mutable reference version of [`Self::merge`], the value is left untouched on error
+            pub fn merge_mut #pruned_impl_generics (self, value: &mut #original_name #ty_generics) -> ::core::result::Result<(), #error_name> #pruned_where_clause {
+                let Self { patch, test } = self;
+                #(#tests)*
+                patch.merge_mut(value);
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum #error_name {
+            /// The operation isn't supported, i.e. `move` and `copy`
+            UnsupportedOperation(&'static str),
+            /// The path doesn't point at a top-level field
+            InvalidPath(::std::string::String),
+            /// The path points at a field that isn't part of the patch
+            UnknownField(::std::string::String),
+            /// The value of a field that isn't an `Option` is `null`
+            NullNotAllowed(&'static str),
+            /// A field that isn't an `Option` is removed
+            RemoveNotAllowed(&'static str),
+            /// The value of a field failed to deserialize
+            InvalidValue {
+                /// The field the value is for
+                field: &'static str,
+                /// Why the value failed to deserialize
+                message: ::std::string::String,
+            },
+            /// The value of a field differs from the one a `test` operation expects
+            TestFailed(&'static str),
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    Self::UnsupportedOperation(op) => write!(f, "unsupported operation `{}`", op),
+                    Self::InvalidPath(path) => write!(f, "path `{}` doesn't point at a top-level field", path),
+                    Self::UnknownField(key) => write!(f, "unknown field `{}`", key),
+                    Self::NullNotAllowed(field) => write!(f, "field `{}` can't be null", field),
+                    Self::RemoveNotAllowed(field) => write!(f, "field `{}` can't be removed", field),
+                    Self::InvalidValue { field, message } => write!(f, "invalid value for field `{}`: {}", field, message),
+                    Self::TestFailed(field) => write!(f, "test of field `{}` failed", field),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            /// Builds the patch from RFC 6902 JSON Patch operations, where `add` and `replace` set a field and `remove`
+            /// clears it. Only top-level paths to fields of the patch are accepted, `move` and `copy` are rejected.
+            /// `test` operations are checked against the original's values (before any change) on merge.
+            pub fn from_json_patch(operations: &[::restructed_types::json_patch::Operation]) -> ::core::result::Result<#wrapper_name #model_ty_generics, #error_name>
+            where
+                #(#bounds,)*
+            {
+                use ::restructed_types::json_patch::Operation;
+
+                let (mut patch, mut test) = (Self::empty(), Self::empty());
+                for operation in operations {
+                    let (target, value) = match operation {
+                        Operation::Add { value, .. } | Operation::Replace { value, .. } => (&mut patch, ::core::option::Option::Some(value)),
+                        Operation::Remove { .. } => (&mut patch, ::core::option::Option::None),
+                        Operation::Test { value, .. } => (&mut test, ::core::option::Option::Some(value)),
+                        Operation::Move { .. } | Operation::Copy { .. } => {
+                            return ::core::result::Result::Err(#error_name::UnsupportedOperation(operation.name()));
+                        }
+                    };
+                    let path = operation.path();
+                    let key = ::restructed_types::json_patch::top_level_member(path)
+                        .ok_or_else(|| #error_name::InvalidPath(path.to_string()))?;
+                    match (key.as_str(), value) {
+                        #(#arms)*
+                        _ => return ::core::result::Result::Err(#error_name::UnknownField(key)),
                     }
-                )*
+                }
+                ::core::result::Result::Ok(#wrapper_name { patch, test })
             }
         }
-        OptionType::Option => {
-            let field: Vec<_> = fields.iter().map(|v| v.0).collect();
-            quote! {
+    }
+}
+
+/// Generates `empty`, `is_empty`, `len` and `changed_fields` for struct patches
+fn impl_introspection(
+    name: &Ident,
+    generics: &ModelGenerics,
+    patch_fields: &[PatchField],
+) -> TokenStream {
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let members: Vec<&Member> = patch_fields.iter().map(|f| &f.member).collect();
+    let undefined = patch_fields.iter().map(|f| f.gen_undefined());
+    let is_set: Vec<TokenStream> = patch_fields
+        .iter()
+        .map(|f| {
+            let member = &f.member;
+            f.gen_is_set(quote!(self.#member))
+        })
+        .collect();
+    let names = patch_fields.iter().map(|f| member_name(&f.original));
+
+    quote! {
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            /// A patch changing nothing, i.e. with every field left unset
+            pub fn empty() -> Self {
+                Self {
+                    #(#members: #undefined,)*
+                }
+            }
+
+            /// Whether merging the patch changes nothing, i.e. no field is set
+            pub fn is_empty(&self) -> bool {
+                true #(&& !(#is_set))*
+            }
+
+            /// The number of fields the patch changes
+            pub fn len(&self) -> usize {
+                0 #(+ #is_set as usize)*
+            }
+
+            /// The names of the original's fields the patch changes, in the order they're declared
+            pub fn changed_fields(&self) -> ::std::vec::Vec<&'static str> {
+                let mut fields = ::std::vec::Vec::new();
                 #(
-                    match self.#field {
-                        ::core::option::Option::Some(v) => value.#field = v,
-                        ::core::option::Option::None => {},
+                    if #is_set {
+                        fields.push(#names);
                     }
                 )*
+                fields
             }
         }
     }
 }
 
-fn impl_from_derived(fields: &[(&Ident, bool)], option: OptionType) -> TokenStream {
-    match option {
-        OptionType::MaybeUndefined => {
-            let option_field: Vec<_> = fields
-                .iter()
-                .filter_map(|(ident, is_option)| is_option.then_some(ident))
-                .collect();
-
-            let required_field: Vec<_> = fields
-                .iter()
-                .filter_map(|(ident, is_option)| (!is_option).then_some(ident))
-                .collect();
-            quote! {
-                #(
-                    #option_field: ::poem_openapi::types::MaybeUndefined::from_opt_undefined(value.#option_field),
-                )*
-                #(
-                    #required_field: ::core::option::Option::Some(value.#required_field),
-                )*
+/// A variant of the patch enum along with the fields it updates
+struct PatchVariant<'a> {
+    variant: &'a syn::Variant,
+    /// The name of the variant on the patch, differs from the original's when renamed
+    name: Ident,
+    fields: Vec<PatchField>,
+    /// Whether every field of the original variant is part of the patch, which is needed to replace the original with it
+    is_complete: bool,
+}
+
+fn impl_for_enum(
+    ast: &DeriveInput,
+    attr: &Attribute,
+    data: &DataEnum,
+    args: &AttrArgs,
+    options: &PatchOptions,
+    on_mismatch: OnMismatch,
+    model_args: &ModelAttrArgs,
+) -> TokenStream {
+    let AttrArgs {
+        name,
+        fields: _,
+        derive,
+        preset,
+        attributes_with,
+        rename,
+    } = args;
+    let original_name = &ast.ident;
+
+    let selected: Vec<_> = data
+        .variants
+        .iter()
+        .filter(|v| args.includes(&Member::Named(v.ident.clone()), &v.attrs, "patch"))
+        .collect();
+    if selected.is_empty() {
+        abort!(attr, "Patch enums need at least one variant of `{}`", original_name)
+    }
+    rename.validate(&selected.iter().map(|v| Member::Named(v.ident.clone())).collect::<Vec<_>>());
+
+    // Build the variants for the new type, wrapping the fields of each in an Option
+    let mut field_types: Vec<&Type> = vec![];
+    let mut variants: Vec<PatchVariant> = vec![];
+    let mut variant_tokens: Vec<TokenStream> = vec![];
+    for variant in selected {
+        let kept: Vec<_> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(i, f)| preset.predicate(f) && args.includes_variant_field(&variant.ident, &field_member(*i, f), &f.attrs, "patch"))
+            .collect();
+        let is_complete = kept.len() == variant.fields.len();
+
+        let mut fields: Vec<PatchField> = vec![];
+        let mut field_tokens: Vec<TokenStream> = vec![];
+        for (patch_index, (i, field)) in kept.into_iter().enumerate() {
+            let patch_field = PatchField::new(
+                match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(patch_index.into()), // Tuple variants are renumbered by what is left
+                },
+                field_member(i, field),
+                &field.ty,
+                None,
+                &options.option,
+            );
+            field_types.push(&field.ty);
+            field_tokens.push(patch_field.gen_definition(&quote!(), field.ident.as_ref(), field, options)); // Variant fields can't have a visibility
+            fields.push(patch_field);
+        }
+
+        let patch_name = rename.rename(&variant.ident);
+        let docs = extract_docs(&variant.attrs);
+        let variant_attrs = attributes_with.gen_field_attributes(variant.attrs.clone());
+        let body = match &variant.fields {
+            syn::Fields::Named(_) => quote!({ #(#field_tokens),* }),
+            syn::Fields::Unnamed(_) => quote!(( #(#field_tokens),* )),
+            syn::Fields::Unit => quote!(),
+        };
+        variant_tokens.push(quote! {
+            #docs
+            #(#variant_attrs)*
+            #patch_name #body
+        });
+        variants.push(PatchVariant { variant, name: patch_name, fields, is_complete });
+    }
+
+    let generics = ModelGenerics::new(&ast.generics, field_types);
+    let (_, ty_generics, _) = generics.original.split_for_impl();
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let (pruned_impl_generics, _, pruned_where_clause) = generics.pruned.split_for_impl();
+    let model_generics = &generics.model;
+
+    let attributes = attributes_with.gen_top_attributes(ast);
+    let derives = gen_derive(derive.as_ref());
+    let allow_lints = rename.gen_allow_lints();
+    let error_name = format_ident!("{}MergeError", name);
+    let impl_merge = impl_enum_merge(original_name, data, &variants, &error_name, on_mismatch);
+    let impl_from = impl_enum_from(original_name, name, data, &variants, &generics);
+
+    // The openapi example delegates to `From`, which only exists when every variant is part of the patch
+    let impl_extras = match variants.len() == data.variants.len() {
+        true => impl_extras(original_name, name, &generics, model_args),
+        false => vec![],
+    };
+
+    let error_doc = format!("Error of merging a [`{name}`] into a [`{original_name}`] of a different variant");
+    let error_display = format!("can't merge variant `{{}}` of `{name}` into variant `{{}}` of `{original_name}`");
+    quote! {
+
+        /// Generated patch model of [`#original_name`]
+        #derives
+        #(#attributes)*
+        #allow_lints
+        pub enum #name #model_generics #model_where_clause {
+            #(#variant_tokens),*
+        }
+
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            /// Merges the updates into the given value, returning the updated value <br/>
+            /// When the variants match, the only fields to change will be the ones that are Some. Otherwise the value is
+            /// replaced or an error is returned, as configured by `on_mismatch`.
+            pub fn merge #pruned_impl_generics (self, mut value: #original_name #ty_generics) -> ::core::result::Result<#original_name #ty_generics, #error_name> #pruned_where_clause {
+                self.merge_mut(&mut value)?;
+                ::core::result::Result::Ok(value)
+            }
+
+            /// Mutable reference version of [`Self::merge`], the value is left untouched on error
+            pub fn merge_mut #pruned_impl_generics (self, value: &mut #original_name #ty_generics) -> ::core::result::Result<(), #error_name> #pruned_where_clause {
+                #impl_merge
+                ::core::result::Result::Ok(())
             }
         }
-        OptionType::Option => {
-            let field: Vec<_> = fields.iter().map(|v| v.0).collect();
-            quote! {
-                #(
-                    #field: ::core::option::Option::Some(value.#field),
-                )*
+
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct #error_name {
+            /// The name of the patch's variant
+            pub patch_variant: &'static str,
+            /// The name of the original's variant
+            pub original_variant: &'static str,
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, #error_display, self.patch_variant, self.original_variant)
             }
         }
+
+        impl ::std::error::Error for #error_name {}
+
+        #impl_from
+        #(#impl_extras)*
     }
 }
 
-fn impl_struct_fields(
-    field_name: &Ident,
-    field_ty: &Type,
-    #[allow(unused_variables)] option_ty: Option<&Type>,
-    docs: &TokenStream,
-    option: OptionType,
-    skip_serializing_double_option: bool,
+/// Generates the body of `merge_mut` for enum patches, matching the patch's variant against the original's
+fn impl_enum_merge(
+    original_name: &Ident,
+    data: &DataEnum,
+    variants: &[PatchVariant],
+    error_name: &Ident,
+    on_mismatch: OnMismatch,
 ) -> TokenStream {
-    match option {
-        OptionType::MaybeUndefined => match option_ty {
-            Some(t) => {
-                quote! {
-                    #docs
-                    pub #field_name: ::poem_openapi::types::MaybeUndefined<#t>
-                }
+    let original_variant = gen_variant_name(quote!(#original_name), data.variants.iter().map(|v| &v.ident), quote!(&*value));
+
+    let mut arms: Vec<TokenStream> = vec![];
+    for PatchVariant { variant, name, fields, .. } in variants {
+        let ident = &variant.ident;
+        let patch = fields.iter().map(|f| {
+            let (member, binding) = (&f.member, f.binding("p"));
+            quote!(#member: #binding)
+        });
+        let original = fields.iter().map(|f| {
+            let (member, binding) = (&f.original, f.binding("o"));
+            quote!(#member: #binding)
+        });
+        let merges = fields.iter().map(|f| {
+            let (patch, original) = (f.binding("p"), f.binding("o"));
+            f.gen_merge(quote!(#patch), quote!(*#original))
+        });
+        arms.push(quote! {
+            (Self::#name { #(#patch),* }, #original_name::#ident { #(#original,)* .. }) => {
+                #(#merges)*
             }
+        });
+    }
+
+    // Replacing needs every field of the original's variant, any the patch doesn't set gives an error instead
+    let replaceable: Vec<_> = variants
+        .iter()
+        .filter(|v| on_mismatch == OnMismatch::Replace && v.is_complete)
+        .collect();
+    for PatchVariant { variant, name, fields, .. } in &replaceable {
+        let (ident, patch_variant) = (&variant.ident, name.to_string());
+        let patch = fields.iter().map(|f| {
+            let (member, binding) = (&f.member, f.binding("p"));
+            quote!(#member: #binding)
+        });
+        let replacement = fields.iter().map(|f| {
+            let (member, binding) = (&f.original, f.binding("p"));
+            quote!(#member: #binding)
+        });
+        let unwrap = match fields.len() {
+            0 => quote!(),
             _ => {
+                let bindings = fields.iter().map(|f| f.binding("p"));
+                let values = fields.iter().map(|f| {
+                    let binding = f.binding("p");
+                    f.gen_replacement(quote!(#binding))
+                });
+                let (bindings, values) = match fields.len() {
+                    1 => (quote!(#(::core::option::Option::Some(#bindings))*), quote!(#(#values)*)),
+                    _ => (quote!((#(::core::option::Option::Some(#bindings)),*)), quote!((#(#values),*))),
+                };
                 quote! {
-                    #docs
-                    pub #field_name: core::option::Option<#field_ty>
+                    let #bindings = #values else {
+                        return ::core::result::Result::Err(#error_name {
+                            patch_variant: #patch_variant,
+                            original_variant: #original_variant,
+                        });
+                    };
                 }
             }
-        },
+        };
+        arms.push(quote! {
+            (Self::#name { #(#patch),* }, value) => {
+                #unwrap
+                *value = #original_name::#ident { #(#replacement),* };
+            }
+        });
+    }
+
+    // Every pairing of variants is already covered when the original only has one or all mismatches are replaced
+    let is_covered = data.variants.len() == 1 || replaceable.len() == variants.len();
+    if !is_covered {
+        let patch_variant = gen_variant_name(quote!(Self), variants.iter().map(|v| &v.name), quote!(&patch));
+        arms.push(quote! {
+            (patch, value) => {
+                return ::core::result::Result::Err(#error_name {
+                    patch_variant: #patch_variant,
+                    original_variant: #original_variant,
+                });
+            }
+        });
+    }
+
+    quote! {
+        match (self, value) {
+            #(#arms)*
+        }
+    }
+}
+
+/// Generates `From<Original>` for enum patches, or `TryFrom<Original>` when only some of the variants are part of it
+fn impl_enum_from(
+    original_name: &Ident,
+    name: &Ident,
+    data: &DataEnum,
+    variants: &[PatchVariant],
+    generics: &ModelGenerics,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.original.split_for_impl();
+    let (_, model_ty_generics, _) = generics.model.split_for_impl();
+
+    let arms = variants.iter().map(|PatchVariant { variant, name, fields, .. }| {
+        let ident = &variant.ident;
+        let original = fields.iter().map(|f| {
+            let (member, binding) = (&f.original, f.binding("v"));
+            quote!(#member: #binding)
+        });
+        let patch = fields.iter().map(|f| {
+            let member = &f.member;
+            let binding = f.binding("v");
+            let value = f.gen_from_original(quote!(#binding));
+            quote!(#member: #value)
+        });
+        quote!(#original_name::#ident { #(#original,)* .. } => Self::#name { #(#patch),* })
+    });
+
+    if variants.len() == data.variants.len() {
+        return quote! {
+            impl #impl_generics ::core::convert::From<#original_name #ty_generics> for #name #model_ty_generics #where_clause {
+                fn from(value: #original_name #ty_generics) -> Self {
+                    match value {
+                        #(#arms,)*
+                    }
+                }
+            }
+        };
+    }
+
+    let (error_name, impl_error) = impl_try_from_error(original_name, name, generics, "patch");
+    let rejected = data
+        .variants
+        .iter()
+        .filter(|v| !variants.iter().any(|p| p.variant.ident == v.ident))
+        .map(|v| {
+            let (ident, variant_name) = (&v.ident, v.ident.to_string());
+            quote!(value @ #original_name::#ident { .. } => return ::core::result::Result::Err(#error_name { value, variant: #variant_name }))
+        });
+    quote! {
+        #impl_error
+
+        impl #impl_generics ::core::convert::TryFrom<#original_name #ty_generics> for #name #model_ty_generics #where_clause {
+            type Error = #error_name #ty_generics;
+
+            fn try_from(value: #original_name #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                ::core::result::Result::Ok(match value {
+                    #(#arms,)*
+                    #(#rejected,)*
+                })
+            }
+        }
+    }
+}
+
+/// Generates a match of the value (given as an expression) of type `ty` to the name of its variant
+fn gen_variant_name<'a>(ty: TokenStream, variants: impl Iterator<Item = &'a Ident>, value: TokenStream) -> TokenStream {
+    let arms = variants.map(|ident| {
+        let name = ident.to_string();
+        quote!(#ty::#ident { .. } => #name)
+    });
+    quote!(match #value { #(#arms),* })
+}
+
+/// A field of the patch model along with the field of the original model it updates
+struct PatchField {
+    /// How the field is accessed on the patch model
+    member: Member,
+    /// How the field is accessed on the original model, differs from `member` when renamed or on tuple structs with omitted fields
+    original: Member,
+    /// Whether the original field is an `Option`
+    is_option: bool,
+    /// The patch model of the field's type (or of the type in its `Option`) when it's updated through it
+    nested: Option<Type>,
+    /// The operations type of the field when it's a collection updated by operations
+    operation: Option<Type>,
+    /// The wrapper of the field when the original field is an `Option`
+    option: OptionType,
+}
+
+impl PatchField {
+    fn new(member: Member, original: Member, ty: &Type, nested: Option<Type>, option: &OptionType) -> Self {
+        Self {
+            member,
+            original,
+            is_option: extract_type_from_option(ty).is_some(),
+            nested,
+            operation: None,
+            option: option.clone(),
+        }
+    }
+
+    /// The name the field is bound to when matching enum variants, prefixed as both the patch and original are bound
+    fn binding(&self, prefix: &str) -> Ident {
+        match &self.original {
+            Member::Named(ident) => format_ident!("{}_{}", prefix, ident),
+            Member::Unnamed(i) => format_ident!("{}{}", prefix, i.index),
+        }
+    }
+
+    fn gen_definition(&self, vis: &TokenStream, field_name: Option<&Ident>, field: &syn::Field, options: &PatchOptions) -> TokenStream {
+        // Nested fields hold the nested patch in place of the original's type, and collections an operation on it
+        let (field_ty, option_ty) = match (&self.nested, &self.operation) {
+            (Some(nested), _) if self.is_option => (syn::parse_quote!(::core::option::Option<#nested>), Some(nested)),
+            (Some(nested), _) => (nested.clone(), None),
+            (None, Some(operation)) => return impl_operation_field(vis, field_name, operation, &extract_docs(&field.attrs), options),
+            (None, None) => (field.ty.clone(), extract_type_from_option(&field.ty)),
+        };
+        impl_struct_fields(vis, field_name, &field_ty, option_ty, &extract_docs(&field.attrs), options)
+    }
+
+    /// Updates the original's field (given as a place expression) with the patch's field (given as an expression)
+    fn gen_merge(&self, patch: TokenStream, original: TokenStream) -> TokenStream {
+        match (&self.option, self.is_option) {
+            (OptionType::MaybeUndefined, true) => quote! {
+                match #patch {
+                    ::poem_openapi::types::MaybeUndefined::Value(v) => #original = ::core::option::Option::Some(v),
+                    ::poem_openapi::types::MaybeUndefined::Null => #original = ::core::option::Option::None,
+                    ::poem_openapi::types::MaybeUndefined::Undefined => {},
+                }
+            },
+            (OptionType::Custom(_), true) => quote! {
+                ::restructed_types::PatchOption::into_maybe(#patch).update(&mut #original);
+            },
+            _ => quote! {
+                match #patch {
+                    ::core::option::Option::Some(v) => #original = v,
+                    ::core::option::Option::None => {},
+                }
+            },
+        }
+    }
+
+    /// Same as [`Self::gen_merge`] for fields with a nested patch, which is merged into the original's field. `none` is
+    /// what's done when the original's field is an `Option` that's `None`, with the nested patch bound to `p`.
+    fn gen_nested_merge(&self, patch: TokenStream, original: TokenStream, none: TokenStream) -> TokenStream {
+        let finish = self.gen_nested_finish();
+        let merged = quote! {
+            match #original.as_mut() {
+                ::core::option::Option::Some(o) => #finish(p.merge_mut(o)),
+                ::core::option::Option::None => #none,
+            }
+        };
+        let cleared = quote!(#original = ::core::option::Option::None);
+        match (&self.option, self.is_option) {
+            (OptionType::MaybeUndefined, true) => quote! {
+                match #patch {
+                    ::poem_openapi::types::MaybeUndefined::Value(p) => #merged,
+                    ::poem_openapi::types::MaybeUndefined::Null => #cleared,
+                    ::poem_openapi::types::MaybeUndefined::Undefined => {},
+                }
+            },
+            (OptionType::Custom(_), true) => quote! {
+                match ::restructed_types::PatchOption::into_maybe(#patch) {
+                    ::restructed_types::Maybe::Value(p) => #merged,
+                    ::restructed_types::Maybe::Null => #cleared,
+                    ::restructed_types::Maybe::Undefined => {},
+                }
+            },
+            (OptionType::Option, true) => quote! {
+                match #patch {
+                    ::core::option::Option::Some(::core::option::Option::Some(p)) => #merged,
+                    ::core::option::Option::Some(::core::option::Option::None) => #cleared,
+                    ::core::option::Option::None => {},
+                }
+            },
+            (_, false) => quote! {
+                if let ::core::option::Option::Some(p) = #patch {
+                    #finish(p.merge_mut(&mut #original));
+                }
+            },
+        }
+    }
+
+    /// The function taking what merging the nested patch returns, spanned at the nested patch so one that can fail to
+    /// merge is reported there
+    fn gen_nested_finish(&self) -> TokenStream {
+        let span = self.nested.as_ref().map_or_else(proc_macro2::Span::call_site, |nested| nested.span());
+        quote_spanned!(span=> ::restructed_types::NestedMerge::finish)
+    }
+
+    /// Whether the patch's field (given as an expression) is set to a value, i.e. neither unset nor cleared
+    fn gen_is_value(&self, patch: TokenStream) -> TokenStream {
+        match (&self.option, self.is_option) {
+            (OptionType::MaybeUndefined, true) => quote!(#patch.is_value()),
+            (OptionType::Custom(_), true) => quote!(::restructed_types::PatchOption::as_maybe(&#patch).is_value()),
+            (OptionType::Option, true) => quote!(::core::matches!(#patch, ::core::option::Option::Some(::core::option::Option::Some(_)))),
+            (_, false) => quote!(#patch.is_some()),
+        }
+    }
+
+    /// Converts the original's field (given as an expression) into the nested patch or the operation setting the
+    /// collection, as is when it's neither
+    fn gen_into_patch(&self, original: TokenStream) -> TokenStream {
+        match (&self.nested, &self.operation, self.is_option) {
+            (Some(_), _, true) => quote!(#original.map(::core::convert::Into::into)),
+            (Some(_), _, false) => quote!(::core::convert::Into::into(#original)),
+            (None, Some(operation), _) => quote!(::std::vec![<#operation>::Set(#original)]),
+            (None, None, _) => original,
+        }
+    }
+
+    /// Whether the patch's field (given as an expression) changes the original's field when merged
+    fn gen_is_set(&self, patch: TokenStream) -> TokenStream {
+        match (&self.option, self.is_option) {
+            _ if self.operation.is_some() => quote!(!#patch.is_empty()),
+            (OptionType::MaybeUndefined, true) => quote!(!#patch.is_undefined()),
+            (OptionType::Custom(_), true) => quote!(!::restructed_types::PatchOption::is_undefined(&#patch)),
+            _ => quote!(#patch.is_some()),
+        }
+    }
+
+    /// The patch's field when it's left unset
+    fn gen_undefined(&self) -> TokenStream {
+        match (&self.option, self.is_option) {
+            _ if self.operation.is_some() => quote!(::std::vec::Vec::new()),
+            (OptionType::MaybeUndefined, true) => quote!(::poem_openapi::types::MaybeUndefined::Undefined),
+            (OptionType::Custom(_), true) => quote!(::restructed_types::PatchOption::undefined()),
+            _ => quote!(::core::option::Option::None),
+        }
+    }
+
+    /// The patch's field changing the original's field to the given value (as an expression), where `None` clears it
+    fn gen_from_changed(&self, value: TokenStream) -> TokenStream {
+        match (&self.option, self.is_option) {
+            _ if self.operation.is_some() => value,
+            (OptionType::MaybeUndefined, true) => quote! {
+                match #value {
+                    ::core::option::Option::Some(v) => ::poem_openapi::types::MaybeUndefined::Value(v),
+                    ::core::option::Option::None => ::poem_openapi::types::MaybeUndefined::Null,
+                }
+            },
+            (OptionType::Custom(_), true) => quote!(::restructed_types::PatchOption::from_option(#value)),
+            _ => quote!(::core::option::Option::Some(#value)),
+        }
+    }
+
+    /// The patch's field holding the original's field (given as an expression)
+    fn gen_from_original(&self, original: TokenStream) -> TokenStream {
+        match (&self.option, self.is_option) {
+            _ if self.operation.is_some() => original,
+            (OptionType::MaybeUndefined, true) => quote!(::poem_openapi::types::MaybeUndefined::from_opt_undefined(#original)),
+            (OptionType::Custom(_), true) => quote!(::restructed_types::PatchOption::from_original(#original)),
+            _ => quote!(::core::option::Option::Some(#original)),
+        }
+    }
+
+    /// The patch's field from a `&serde_json::Value` (given as an expression), where `null` clears it. Returns early
+    /// with the `NullNotAllowed` or `InvalidValue` variant of `error_name` when the value doesn't fit the field.
+    fn gen_from_json(&self, value: TokenStream, ty: &Type, key: &str, error_name: &Ident) -> TokenStream {
+        let (inner, null) = match extract_type_from_option(ty).filter(|_| self.is_option) {
+            Some(inner) => (inner, self.gen_from_changed(quote!(::core::option::Option::None))),
+            None => (ty, quote!(return ::core::result::Result::Err(#error_name::NullNotAllowed(#key)))),
+        };
+        let parsed = match &self.nested {
+            Some(nested) => quote!(<#nested>::from_merge_patch(v)),
+            None => quote!(::serde_json::from_value::<#inner>(v.clone())),
+        };
+        let parsed = quote! {
+            #parsed.map_err(|e| #error_name::InvalidValue { field: #key, message: e.to_string() })?
+        };
+        let parsed = match self.is_option {
+            true => self.gen_from_changed(quote!(::core::option::Option::Some(#parsed))),
+            false => self.gen_from_original(parsed),
+        };
+        quote! {
+            match #value {
+                ::serde_json::Value::Null => #null,
+                v => #parsed,
+            }
+        }
+    }
+
+    /// The original's field from the patch's field (given as an expression) as an `Option`, being `None` when not set
+    fn gen_replacement(&self, patch: TokenStream) -> TokenStream {
+        match (&self.option, self.is_option) {
+            (OptionType::MaybeUndefined, true) => quote! {
+                match #patch {
+                    ::poem_openapi::types::MaybeUndefined::Value(v) => ::core::option::Option::Some(::core::option::Option::Some(v)),
+                    ::poem_openapi::types::MaybeUndefined::Null => ::core::option::Option::Some(::core::option::Option::None),
+                    ::poem_openapi::types::MaybeUndefined::Undefined => ::core::option::Option::None,
+                }
+            },
+            (OptionType::Custom(_), true) => quote!(::restructed_types::PatchOption::into_maybe(#patch).into_option()),
+            _ => patch,
+        }
+    }
+}
+
+fn impl_struct_fields(
+    vis: &TokenStream,
+    field_name: Option<&Ident>,
+    field_ty: &Type,
+    option_ty: Option<&Type>,
+    docs: &TokenStream,
+    options: &PatchOptions,
+) -> TokenStream {
+    let field_name = field_name.map(|v| quote!(#v:));
+    let Some(t) = option_ty else {
+        return quote! {
+            #docs
+            #vis #field_name ::core::option::Option<#field_ty>
+        };
+    };
+
+    let wrapper = options.option.wrap(t);
+    let serde = match &options.option {
         OptionType::Option => {
-            let skip_serializing_if = match skip_serializing_double_option && option_ty.is_some()
-            {
+            // Deserializing `null` clears the field, so an unset one can't be serialized as `null` either
+            let skip_serializing_if = match options.skip_serializing_double_option || options.double_option.is_some() {
                 true => quote! {
                    #[serde(skip_serializing_if = "::core::option::Option::is_none")]
                 },
                 false => quote! {},
             };
+            let deserialize_with = match &options.double_option {
+                Some(helper) => {
+                    let helper = helper.to_string();
+                    quote!(#[serde(default, deserialize_with = #helper)])
+                }
+                None => quote! {},
+            };
             quote! {
-                #docs
                 #skip_serializing_if
-                pub #field_name: core::option::Option<#field_ty>
+                #deserialize_with
             }
         }
+        OptionType::MaybeUndefined => quote! {},
+        // Without these, a missing field fails to deserialize and an undefined one is serialized
+        OptionType::Custom(_) if options.serde => quote! {
+            #[serde(
+                default = "::restructed_types::PatchOption::undefined",
+                skip_serializing_if = "::restructed_types::PatchOption::is_undefined"
+            )]
+        },
+        OptionType::Custom(_) => quote! {},
+    };
+    quote! {
+        #docs
+        #serde
+        #vis #field_name #wrapper
+    }
+}
+
+/// Generates the field of a collection updated by operations, applied in order so one patch can e.g. both add and
+/// remove elements. An empty list leaves the collection unchanged.
+fn impl_operation_field(
+    vis: &TokenStream,
+    field_name: Option<&Ident>,
+    operation: &Type,
+    docs: &TokenStream,
+    options: &PatchOptions,
+) -> TokenStream {
+    let field_name = field_name.map(|v| quote!(#v:));
+    let serde = match options.serde {
+        true => quote! {
+            #[serde(default, skip_serializing_if = "::std::vec::Vec::is_empty")]
+        },
+        false => quote! {},
+    };
+    quote! {
+        #docs
+        #serde
+        #vis #field_name ::std::vec::Vec<#operation>
     }
 }
 
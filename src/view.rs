@@ -1,10 +1,10 @@
-use crate::logic::{args::AttrArgs, *};
+use crate::logic::{args::{AttrArgs, ComputeArg, KindArgs, MapArg, NestedArg, RefArgs}, case::RenameRule, *};
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::abort;
-use quote::quote;
-use syn::{self, Attribute, DataEnum, DataStruct, DeriveInput};
+use quote::{format_ident, quote};
+use syn::{self, ext::IdentExt, Attribute, DataEnum, DataStruct, DeriveInput, Member};
 
-use self::args::ModelAttrArgs;
+use self::{args::ModelAttrArgs, generics::ModelGenerics};
 
 pub fn impl_view_model(
     ast: &DeriveInput,
@@ -12,49 +12,96 @@ pub fn impl_view_model(
     model_args: &ModelAttrArgs
 ) -> TokenStream {
     // Argument and Variable Initialization and Prep
-    let (args, _) = AttrArgs::parse(attr, model_args, true);
+    let (args, mut remainder) = AttrArgs::parse(attr, model_args, false);
     let AttrArgs {
         name,
         fields: _,
         derive,
         preset: _,
-        attributes_with
+        attributes_with,
+        rename,
     } = args.clone();
 
+    let maps = MapArg::parse(&mut remainder, model_args);
+    let computed = ComputeArg::parse(&mut remainder, model_args);
+    let nested = NestedArg::parse(&mut remainder, model_args, "view");
+    let ref_args = RefArgs::parse(&mut remainder, attr, model_args);
+    let from_ref = take_ident_bool("from_ref", &mut remainder).unwrap_or_default();
+    let default_omitted = take_ident_bool("default_omitted", &mut remainder).unwrap_or_default();
+
+    AttrArgs::abort_unexpected(&remainder, &["map", "compute", "nested", "ref", "to_owned", "ref_derive", "from_ref", "default_omitted"]);
+    if from_ref && model_args.extras.is_enum {
+        abort!(attr, "`from_ref` is only supported on struct views")
+    }
+
     let original_name = &ast.ident;
     let is_struct = matches!(&ast.data, syn::Data::Struct(_));
     let mut field_mapping: Vec<TokenStream> = vec![]; // Will contain each fields `From` trait impl
     let mut field_mapping_reverse: Vec<TokenStream> = vec![];
+    let mut field_types: Vec<syn::Type> = vec![]; // Types the view still holds, used to work out which generics are kept
+    let mut view_fields: Vec<ViewField> = vec![];
+    let mut removed_fields: Vec<(String, &syn::Type)> = vec![]; // Fields left out of the kept variants of enum views
 
     // Generate Implementation
     let field_tokens: Vec<_> = match &ast.data {
-        syn::Data::Struct(data) => impl_for_struct(data, &mut field_mapping, &mut field_mapping_reverse, &args),
-        syn::Data::Enum(data) => impl_for_enum(data, &mut field_mapping, &mut field_mapping_reverse, &args, original_name),
+        syn::Data::Struct(data) => impl_for_struct(data, &mut field_mapping, &mut field_types, &mut view_fields, &args, &maps, &computed, &nested),
+        syn::Data::Enum(data) => {
+            let (variants, removed) = impl_for_enum(data, &mut field_mapping, &mut field_mapping_reverse, &mut field_types, &args, &maps, original_name);
+            removed_fields = removed;
+            variants
+        }
         syn::Data::Union(_) => abort!(attr, "Patch Model can only be derived for `struct` & `enum`, NOT `union`"),
     };
+    let generics = ModelGenerics::new(&ast.generics, &field_types);
+    let (_, _, where_clause) = generics.model.split_for_impl();
+    let model_generics = &generics.model;
 
-    let structure = match is_struct {
-        true => quote!(struct),
-        false => quote!(enum),
+    let (structure, body) = match &ast.data {
+        syn::Data::Struct(data) => (quote!(struct), gen_struct_body(&data.fields, &field_tokens, where_clause)),
+        _ => (quote!(enum), gen_struct_body(&syn::Fields::Unit, &field_tokens, where_clause)),
     };
-    
+
 
     let attributes = attributes_with.gen_top_attributes(ast);
     let derives = gen_derive(derive.as_ref());
-    
-    let impl_from = impl_from_trait(original_name, &name, field_mapping, field_mapping_reverse, is_struct);
-    let impl_extras = impl_extras(original_name, &name, model_args);
+    let allow_lints = rename.gen_allow_lints();
+
+    if let Some((field, _)) = removed_fields.first().filter(|_| !default_omitted) {
+        abort!(
+            attr,
+            "Field `{}` isn't part of `{}`, add `default_omitted = true` to fill it in with `Default` for `From<{}> for {}`",
+            field, name, name, original_name
+        )
+    }
+    let default_bounds = removed_fields.iter().map(|(_, ty)| syn::parse_quote!(#ty: ::core::default::Default)).collect();
+
+    let impl_from = impl_from_trait(original_name, &name, &generics, field_mapping, field_mapping_reverse, default_bounds, is_struct);
+    let impl_into_original = match &ast.data {
+        syn::Data::Struct(data) => impl_into_original(original_name, &name, &generics, &data.fields, &view_fields, default_omitted),
+        _ => quote!(),
+    };
+    let impl_from_ref = match from_ref {
+        true => impl_from_ref_trait(original_name, &name, &generics, &view_fields, &computed),
+        false => quote!(),
+    };
+    let impl_extras = impl_extras(original_name, &name, &generics, model_args);
+    let impl_ref = match (&ref_args, &ast.data) {
+        (Some(ref_args), syn::Data::Struct(data)) => impl_ref_view(original_name, &name, &generics, &data.fields, &view_fields, &computed, ref_args),
+        _ => quote!(),
+    };
 
     let doc_string = format!("This is a restructured (View) model of ['{original_name}']. Refer to the original model for more structual documentation.");
     quote! {
         #[doc= #doc_string]
         #derives
         #(#attributes)*
-        pub #structure #name {
-            #(#field_tokens),*
-        }
+        #allow_lints
+        pub #structure #name #model_generics #body
 
         #impl_from
+        #impl_into_original
+        #impl_from_ref
+        #impl_ref
         #(#impl_extras)*
     }
 }
@@ -63,14 +110,21 @@ pub fn impl_view_model(
 fn impl_from_trait(
     original_name: &Ident,
     name: &Ident,
+    generics: &ModelGenerics,
     field_mapping: Vec<TokenStream>,
     field_mapping_reverse: Vec<TokenStream>,
+    default_bounds: Vec<syn::WherePredicate>,
     is_struct: bool,
 ) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.original.split_for_impl();
+    let default_where = generics.original_where_with(default_bounds);
+    let (_, model_ty_generics, _) = generics.model.split_for_impl();
+    let (error_name, impl_error) = impl_try_from_error(original_name, name, generics, "view");
+
     if is_struct {
         quote! {
-            impl ::core::convert::From<#original_name> for #name  {
-                fn from(value: #original_name) -> Self {
+            impl #impl_generics ::core::convert::From<#original_name #ty_generics> for #name #model_ty_generics #where_clause {
+                fn from(value: #original_name #ty_generics) -> Self {
                     Self {
                         #(#field_mapping),*
                     }
@@ -79,21 +133,22 @@ fn impl_from_trait(
         }
     } else {
         quote! {
-            impl ::core::convert::From<#name> for #original_name  {
-                fn from(value: #name) -> Self {
+            impl #impl_generics ::core::convert::From<#name #model_ty_generics> for #original_name #ty_generics #default_where {
+                fn from(value: #name #model_ty_generics) -> Self {
                     match value {
                         #(#field_mapping),*
                     }
                 }
             }
 
-            impl ::core::convert::TryFrom<#original_name> for #name {
-                type Error = ();
+            #impl_error
 
-                fn try_from(value: #original_name) -> Result<Self, Self::Error> {
-                    Ok(match value {
+            impl #impl_generics ::core::convert::TryFrom<#original_name #ty_generics> for #name #model_ty_generics #where_clause {
+                type Error = #error_name #ty_generics;
+
+                fn try_from(value: #original_name #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                    ::core::result::Result::Ok(match value {
                         #(#field_mapping_reverse, )*
-                        _ => return Err(())
                     })
                 }
             }
@@ -101,117 +156,558 @@ fn impl_from_trait(
     }
 }
 
+/// Handles the opt-in `From<&OriginalModel>` of struct views, cloning only the fields the view holds
+fn impl_from_ref_trait(
+    original_name: &Ident,
+    name: &Ident,
+    generics: &ModelGenerics,
+    fields: &[ViewField],
+    computed: &[ComputeArg],
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.original.split_for_impl();
+    let (_, model_ty_generics, _) = generics.model.split_for_impl();
+
+    let mut field_mapping: Vec<TokenStream> = vec![];
+    let mut cloned_types: Vec<syn::WherePredicate> = vec![];
+    for ViewField { field, member, original, map, nested } in fields {
+        let value = match (map, nested) {
+            (Some(map), _) => map.gen_forward_borrowed(quote!(value.#original)),
+            (None, Some(nested)) => nested.gen_view(&field.ty, quote!(::core::clone::Clone::clone(&value.#original))).1,
+            (None, None) => quote!(::core::clone::Clone::clone(&value.#original)),
+        };
+        if map.is_none_or(|m| m.forward.is_none()) {
+            let ty = &field.ty;
+            cloned_types.push(syn::parse_quote!(#ty: ::core::clone::Clone));
+        }
+        field_mapping.push(quote!(#member: #value));
+    }
+    for c in computed {
+        let (field_name, value) = (&c.name, c.gen_value(quote!(*value)));
+        field_mapping.push(quote!(#field_name: #value));
+    }
+    // Cloned fields are bound so generic ones don't need to be `Clone` unless this impl is used
+    let where_clause = generics.original_where_with(cloned_types);
+
+    quote! {
+        impl #impl_generics ::core::convert::From<&#original_name #ty_generics> for #name #model_ty_generics #where_clause {
+            fn from(value: &#original_name #ty_generics) -> Self {
+                Self {
+                    #(#field_mapping),*
+                }
+            }
+        }
+    }
+}
+/// Handles converting struct views back into the original, taking the fields the view doesn't hold as arguments of
+/// `into_original`. With `default_omitted` they're filled in by [`Default`] for `From<View> for Original` instead.
+fn impl_into_original(
+    original_name: &Ident,
+    name: &Ident,
+    generics: &ModelGenerics,
+    data_fields: &syn::Fields,
+    fields: &[ViewField],
+    default_omitted: bool,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.original.split_for_impl();
+    let (model_impl_generics, model_ty_generics, model_where_clause) = generics.model.split_for_impl();
+    let (pruned_impl_generics, _, pruned_where_clause) = generics.pruned.split_for_impl();
+
+    // Fields mapped without a reverse function or nested can't be converted back, so they're taken like omitted fields
+    let mut field_mapping: Vec<TokenStream> = vec![];
+    let mut params: Vec<TokenStream> = vec![];
+    let mut default_types: Vec<syn::WherePredicate> = vec![];
+    for (i, field) in data_fields.iter().enumerate() {
+        let original = field_member(i, field);
+        let kept = fields.iter().find(|f| f.original == original).and_then(|f| {
+            let member = &f.member;
+            match (f.map, f.nested) {
+                (Some(map), _) => map.gen_reverse(quote!(self.#member)),
+                (None, Some(_)) => None,
+                (None, None) => Some(quote!(self.#member)),
+            }
+        });
+        match kept {
+            Some(value) => field_mapping.push(quote!(#original: #value)),
+            None => {
+                let (param, ty) = (omitted_param(i, field), &field.ty);
+                field_mapping.push(quote!(#original: #param));
+                params.push(quote!(#param: #ty));
+                default_types.push(syn::parse_quote!(#ty: ::core::default::Default));
+            }
+        }
+    }
+
+    let impl_from = default_omitted.then(|| {
+        let defaults = params.iter().map(|_| quote!(::core::default::Default::default()));
+        let where_clause = generics.original_where_with(default_types);
+        quote! {
+            impl #impl_generics ::core::convert::From<#name #model_ty_generics> for #original_name #ty_generics #where_clause {
+                fn from(value: #name #model_ty_generics) -> Self {
+                    value.into_original(#(#defaults),*)
+                }
+            }
+        }
+    });
+
+    let doc_string = format!("Converts the view back into [`{original_name}`], taking the fields it doesn't hold as arguments");
+    quote! {
+        impl #model_impl_generics #name #model_ty_generics #model_where_clause {
+            #[doc = #doc_string]
+            #[allow(clippy::too_many_arguments)]
+            pub fn into_original #pruned_impl_generics (self, #(#params),*) -> #original_name #ty_generics #pruned_where_clause {
+                #original_name {
+                    #(#field_mapping),*
+                }
+            }
+        }
+
+        #impl_from
+    }
+}
+
+/// The name of the `into_original` argument for an omitted field, tuple struct fields are named by their index
+fn omitted_param(index: usize, field: &syn::Field) -> Ident {
+    match &field.ident {
+        Some(ident) => ident.clone(),
+        None => format_ident!("field_{}", index),
+    }
+}
 
-fn impl_for_struct(data: &DataStruct, field_mapping: &mut Vec<TokenStream>, field_mapping_reverse: &mut Vec<TokenStream>, args: &AttrArgs) -> Vec<TokenStream> {
+#[allow(clippy::too_many_arguments)]
+fn impl_for_struct<'a>(data: &'a DataStruct, field_mapping: &mut Vec<TokenStream>, field_types: &mut Vec<syn::Type>, view_fields: &mut Vec<ViewField<'a>>, args: &AttrArgs, maps: &'a [MapArg], computed: &'a [ComputeArg], nested: &'a [NestedArg]) -> Vec<TokenStream> {
     let AttrArgs {
         name: _,
-        fields,
+        fields: _,
         derive: _,
         preset,
-        attributes_with
+        attributes_with,
+        rename,
     } = args;
 
+    let selected: Vec<_> = data
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(i, f)| preset.predicate(f) && args.includes(&field_member(*i, f), &f.attrs, "view"))
+        .collect();
+    let selected_members: Vec<_> = selected.iter().map(|(i, f)| field_member(*i, f)).collect();
+    rename.validate(&selected_members);
+    if let Some(map) = maps.iter().find(|m| !selected_members.contains(&m.field)) {
+        abort!(map.ty, "Field `{}` can't be mapped as it isn't part of this view", member_name(&map.field))
+    }
+    NestedArg::validate(nested, &selected_members, "view");
+    if let Some(n) = nested.iter().find(|n| MapArg::find(maps, None, &n.field).is_some()) {
+        abort!(n.field, "Field `{}` can't be both mapped and nested", member_name(&n.field))
+    }
 
-    data
-            .fields
-            .iter()
-            .filter(|f| {
-                preset.predicate(f) && fields.predicate(f.ident.as_ref().expect("Field must be named")) 
-            })
-            .map(|field| {
+    let mut field_tokens: Vec<TokenStream> = selected
+            .into_iter()
+            .enumerate()
+            .map(|(view_index, (i, field))| {
                 let vis = &field.vis;
                 let docs = extract_docs(&field.attrs);
-                let field_name = &field.ident.as_ref().unwrap();
-                let field_ty = &field.ty;
+                let original_member = field_member(i, field);
+                let member = match &field.ident {
+                    Some(ident) => Member::Named(rename.rename(ident)),
+                    None => Member::Unnamed(view_index.into()), // Tuple structs are renumbered by what is left
+                };
+                let map = MapArg::find(maps, None, &original_member);
+                let nested = NestedArg::find(nested, &original_member);
 
                 let field_attr = attributes_with.gen_field_attributes(field.attrs.clone());
 
-                let mapping = quote!(#field_name: value.#field_name);
-                field_mapping.push(mapping.clone());
-                field_mapping_reverse.push(mapping);
-                
+                let (field_ty, value) = match (map, nested) {
+                    (Some(map), _) => (map.ty.clone(), map.gen_forward(quote!(value.#original_member))),
+                    (None, Some(nested)) => nested.gen_view(&field.ty, quote!(value.#original_member)),
+                    (None, None) => (field.ty.clone(), quote!(value.#original_member)),
+                };
+                field_mapping.push(quote!(#member: #value));
+                field_types.push(field_ty.clone());
+
+                let field_name = match &member {
+                    Member::Named(v) => Some(quote!(#v:)),
+                    Member::Unnamed(_) => None,
+                };
+                view_fields.push(ViewField { field, member, original: original_member, map, nested });
                 quote! {
                     #docs
                     #(#field_attr)*
-                    #vis #field_name: #field_ty
+                    #vis #field_name #field_ty
                 }
             })
-            .collect()
+            .collect();
+
+    // Computed fields go first in the mapping so they borrow the original before any of its fields are moved out
+    let names: Vec<Ident> = selected_members
+        .iter()
+        .filter_map(|m| match m {
+            Member::Named(ident) => Some(rename.rename(ident)),
+            Member::Unnamed(_) => None,
+        })
+        .collect();
+    ComputeArg::validate(computed, &names);
+    for c in computed.iter().rev() {
+        let (field_name, value) = (&c.name, c.gen_value(quote!(value)));
+        field_mapping.insert(0, quote!(#field_name: #value));
+    }
+    for c in computed {
+        let (field_name, field_ty) = (&c.name, &c.ty);
+        field_types.push(field_ty.clone());
+        field_tokens.push(quote!(pub #field_name: #field_ty));
+    }
+
+    field_tokens
 }
 
-fn impl_for_enum(data: &DataEnum, field_mapping: &mut Vec<TokenStream>, field_mapping_reverse: &mut Vec<TokenStream>, args: &AttrArgs, original_name: &Ident) -> Vec<TokenStream> {
+/// Generates the variants of an enum view. Returns along with them the fields left out of the kept variants (named
+/// `Variant::field`), which `From<View> for Original` has to fill in.
+fn impl_for_enum<'a>(data: &'a DataEnum, field_mapping: &mut Vec<TokenStream>, field_mapping_reverse: &mut Vec<TokenStream>, field_types: &mut Vec<syn::Type>, args: &AttrArgs, maps: &'a [MapArg], original_name: &Ident) -> (Vec<TokenStream>, Vec<(String, &'a syn::Type)>) {
     let AttrArgs {
         name,
-        fields,
+        fields: _,
         derive: _,
         preset,
-        attributes_with
+        attributes_with,
+        rename,
     } = args;
 
-    data.variants
-    .iter()
-    .filter(|v| fields.predicate(&v.ident))
-    .map(|field| {
-        let mut field_impl = field.clone();
-        field_impl.attrs = attributes_with.gen_field_attributes(field_impl.attrs);
+    let selected: Vec<_> = data.variants
+        .iter()
+        .filter(|v| args.includes(&Member::Named(v.ident.clone()), &v.attrs, "view"))
+        .collect();
+    rename.validate(&selected.iter().map(|v| Member::Named(v.ident.clone())).collect::<Vec<_>>());
+    for map in maps {
+        if !selected.iter().any(|v| map.variant.as_ref() == Some(&v.ident)) {
+            abort!(map.variant, "Variant `{}` can't be mapped as it isn't part of this view", map.variant.as_ref().unwrap())
+        }
+    }
 
-        let docs = extract_docs(&field.attrs);
-        let field_name = &field.ident;
-
-        match &field.fields {
-            syn::Fields::Unit => {
-                field_mapping.push(quote!{
-                    #name::#field_name => #original_name::#field_name
-                });
-                field_mapping_reverse.push(quote!{
-                    #original_name::#field_name => #name::#field_name
-                });
-            },
-            syn::Fields::Unnamed(_) => {
-                let variant_args: Vec<_> = field
-                    .fields
-                    .iter()
-                    .filter(|f| preset.predicate(f))
-                    .enumerate()
-                    .map(|(i, _)| {
-                        let c = (i as u8 + b'a') as char;
-                        let c= syn::Ident::new(&c.to_string(), proc_macro2::Span::call_site()); // convert char to ident so it wont be "quoted"
-
-                        quote!(#c)
-                    })
-                    .collect();
-                field_mapping.push(quote!{
-                    #name::#field_name(#(#variant_args),*) => #original_name::#field_name(#(#variant_args),*)
-                });
-                field_mapping_reverse.push(quote!{
-                    #original_name::#field_name(#(#variant_args),*) => #name::#field_name(#(#variant_args),*)
-                });
-            }
-            syn::Fields::Named(n) => {
-                let variant_args: Vec<_> = n
-                    .named
-                    .iter()
-                    .filter(|f| preset.predicate(f))
-                    .map(|f| {
-
-                        let arg = f.ident.as_ref();
-                        quote!(#arg)
-                    })
-                    .collect();
-                field_mapping.push(quote!{
-                    #name::#field_name{#(#variant_args),*} => #original_name::#field_name{#(#variant_args),*}
-                });
-                field_mapping_reverse.push(quote!{
-                    #original_name::#field_name{#(#variant_args),*} => #name::#field_name{#(#variant_args),*}
-                });
-            },
+    // Variants left out of the view are rejected by `TryFrom`, giving back the value along with the variant's name
+    let error_name = try_from_error_name(name);
+    for variant in data.variants.iter().filter(|v| !selected.iter().any(|s| s.ident == v.ident)) {
+        let (ident, variant_name) = (&variant.ident, variant.ident.to_string());
+        field_mapping_reverse.push(quote! {
+            value @ #original_name::#ident { .. } => return ::core::result::Result::Err(#error_name { value, variant: #variant_name })
+        });
+    }
+
+    let mut removed = vec![];
+    let variants = selected
+    .into_iter()
+    .map(|variant| {
+        let view_name = rename.rename(&variant.ident);
+        let variant_name = &variant.ident;
+        let docs = extract_docs(&variant.attrs);
+        let attrs = attributes_with.gen_field_attributes(variant.attrs.clone());
+        let discriminant = variant.discriminant.as_ref().map(|(eq, expr)| quote!(#eq #expr));
+
+        // Both directions use brace patterns, so kept fields of tuple variants are renumbered on the view's side
+        let mut definitions: Vec<TokenStream> = vec![];
+        let mut pattern: Vec<TokenStream> = vec![];
+        let mut view_pattern: Vec<TokenStream> = vec![];
+        let mut to_view: Vec<TokenStream> = vec![];
+        let mut to_original: Vec<TokenStream> = vec![];
+        for (i, f) in variant.fields.iter().enumerate() {
+            let member = field_member(i, f);
+            let map = MapArg::find(maps, Some(variant_name), &member);
+            if !(preset.predicate(f) && args.includes_variant_field(variant_name, &member, &f.attrs, "view")) {
+                if let Some(map) = map {
+                    abort!(map.field, "Field `{}` of variant `{}` can't be mapped as it isn't part of this view", member_name(&member), variant_name)
+                }
+                removed.push((format!("{}::{}", variant_name, member_name(&member)), &f.ty));
+                to_original.push(quote!(#member: ::core::default::Default::default()));
+                continue;
+            }
+
+            let view_member = match &f.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(definitions.len().into()),
+            };
+            let binding = match &f.ident {
+                Some(ident) => ident.clone(),
+                None => format_ident!("v{}", i),
+            };
+            let (ty, view_value, original_value) = match map {
+                Some(map) => {
+                    let reverse = map.gen_reverse(quote!(#binding)).unwrap_or_else(|| abort!(
+                        map.ty,
+                        "Mapped fields of enum views need a reverse function for `From<{}> for {}`, e.g. `map({}::{}: {} = (forward, reverse))`",
+                        name, original_name, variant_name, member_name(&member), quote::ToTokens::to_token_stream(&map.ty)
+                    ));
+                    (&map.ty, map.gen_forward(quote!(#binding)), reverse)
+                },
+                None => (&f.ty, quote!(#binding), quote!(#binding)),
+            };
+            field_types.push(ty.clone());
+
+            let field_attrs = f.attrs.iter().filter(|a| !is_attribute(a, "view") && !is_attribute(a, "patch"));
+            let field_name = f.ident.as_ref().map(|ident| quote!(#ident:));
+            definitions.push(quote!(#(#field_attrs)* #field_name #ty));
+            // Named fields are bound by their own name, which patterns have to use the shorthand for
+            match &f.ident {
+                Some(_) => {
+                    pattern.push(quote!(#binding));
+                    view_pattern.push(quote!(#binding));
+                }
+                None => {
+                    pattern.push(quote!(#member: #binding));
+                    view_pattern.push(quote!(#view_member: #binding));
+                }
+            }
+            to_view.push(quote!(#view_member: #view_value));
+            to_original.push(quote!(#member: #original_value));
+        }
+
+        field_mapping.push(quote!{
+            #name::#view_name { #(#view_pattern),* } => #original_name::#variant_name { #(#to_original),* }
+        });
+        field_mapping_reverse.push(quote!{
+            #original_name::#variant_name { #(#pattern,)* .. } => #name::#view_name { #(#to_view),* }
+        });
+
+        let body = match &variant.fields {
+            syn::Fields::Unit => quote!(),
+            syn::Fields::Unnamed(_) => quote!((#(#definitions),*)),
+            syn::Fields::Named(_) => quote!({ #(#definitions),* }),
         };
-        
         quote! {
             #docs
-            #field_impl
+            #(#attrs)*
+            #view_name #body #discriminant
         }
     })
-    .collect()
+    .collect();
+
+    (variants, removed)
+}
+
+/// A field of a struct view along with the field of the original model it comes from
+struct ViewField<'a> {
+    /// The field of the original model
+    field: &'a syn::Field,
+    /// How the field is accessed on the view, differs from `original` when renamed or on tuple structs with omitted fields
+    member: Member,
+    /// How the field is accessed on the original model
+    original: Member,
+    /// The mapping applied to the field, if any
+    map: Option<&'a MapArg>,
+    /// The nested view the field is projected into, if any
+    nested: Option<&'a NestedArg>,
+}
+
+/// Generates the borrowing version of a struct view, i.e. `{Name}Ref<'a>`, along with `as_{name}(&self)` on the original model
+fn impl_ref_view(
+    original_name: &Ident,
+    name: &Ident,
+    generics: &ModelGenerics,
+    data_fields: &syn::Fields,
+    fields: &[ViewField],
+    computed: &[ComputeArg],
+    ref_args: &RefArgs,
+) -> TokenStream {
+    if let Some(map) = fields.iter().find_map(|f| f.map) {
+        abort!(map.ty, "Mapped fields can't be borrowed by a ref view as their type changes, remove the `map` or `ref` argument")
+    } else if let Some(nested) = fields.iter().find_map(|f| f.nested) {
+        abort!(nested.field, "Nested fields can't be borrowed by a ref view as their type changes, remove the `nested` or `ref` argument")
+    }
+
+    let ref_name = format_ident!("{}Ref", name);
+    let as_ref_name = format_ident!("as_{}", RenameRule::Snake.apply(&name.to_string()));
+    let lifetime = ref_lifetime(&generics.original);
+    let with_lifetime = |generics: &syn::Generics| {
+        let mut generics = generics.clone();
+        generics.params.insert(0, syn::GenericParam::Lifetime(syn::LifetimeParam::new(lifetime.clone())));
+        generics
+    };
+    let ref_generics = with_lifetime(&generics.model);
+    let ref_impl_generics = with_lifetime(&generics.original);
+    let (original_impl_generics, ty_generics, where_clause) = generics.original.split_for_impl();
+    let (impl_generics, _, _) = ref_impl_generics.split_for_impl();
+    let (ref_generics_impl, ref_ty_generics, ref_where_clause) = ref_generics.split_for_impl();
+    let (_, model_ty_generics, _) = generics.model.split_for_impl();
+
+    let mut field_tokens: Vec<TokenStream> = vec![];
+    let mut borrowed: Vec<TokenStream> = vec![];
+    let mut owned: Vec<TokenStream> = vec![];
+    let mut owned_types: Vec<&syn::Type> = vec![];
+    for ViewField { field, member, original, .. } in fields {
+        let (vis, ty) = (&field.vis, &field.ty);
+        let docs = extract_docs(&field.attrs);
+        let field_name = match member {
+            Member::Named(v) => Some(quote!(#v:)),
+            Member::Unnamed(_) => None,
+        };
+        field_tokens.push(quote! {
+            #docs
+            #vis #field_name &#lifetime #ty
+        });
+        borrowed.push(quote!(#member: &value.#original));
+        owned.push(quote!(#member: ::core::clone::Clone::clone(self.#member)));
+        owned_types.push(ty);
+    }
+    for c in computed {
+        let (field_name, field_ty) = (&c.name, &c.ty);
+        let value = c.gen_value(quote!(*value));
+        field_tokens.push(quote!(pub #field_name: #field_ty));
+        borrowed.push(quote!(#field_name: #value));
+        owned.push(quote!(#field_name: ::core::clone::Clone::clone(&self.#field_name)));
+        owned_types.push(field_ty);
+    }
+
+    let body = gen_struct_body(data_fields, &field_tokens, ref_where_clause);
+    let derives = ref_args.derive.as_ref().map(|d| gen_derive(Some(d)));
+    let impl_to_owned = ref_args.to_owned.then(|| {
+        // Bounds are on the method as generic fields aren't necessarily `Clone`
+        let mut owned_where = ref_generics.clone();
+        owned_where
+            .make_where_clause()
+            .predicates
+            .extend(owned_types.iter().map(|ty| -> syn::WherePredicate { syn::parse_quote!(#ty: ::core::clone::Clone) }));
+        let owned_where = &owned_where.where_clause;
+        let doc_string = format!("Clones the borrowed fields into an owned [`{name}`]");
+        quote! {
+            impl #ref_generics_impl #ref_name #ref_ty_generics #ref_where_clause {
+                #[doc = #doc_string]
+                pub fn to_owned(&self) -> #name #model_ty_generics #owned_where {
+                    #name {
+                        #(#owned),*
+                    }
+                }
+            }
+        }
+    });
+
+    let doc_string = format!("Borrowed version of [`{name}`], created from a reference to [`{original_name}`] without cloning it");
+    quote! {
+        #[doc = #doc_string]
+        #derives
+        pub struct #ref_name #ref_generics #body
+
+        impl #impl_generics ::core::convert::From<&#lifetime #original_name #ty_generics> for #ref_name #ref_ty_generics #where_clause {
+            fn from(value: &#lifetime #original_name #ty_generics) -> Self {
+                Self {
+                    #(#borrowed),*
+                }
+            }
+        }
+
+        impl #original_impl_generics #original_name #ty_generics #where_clause {
+            #[doc = #doc_string]
+            pub fn #as_ref_name<#lifetime>(&#lifetime self) -> #ref_name #ref_ty_generics {
+                ::core::convert::From::from(self)
+            }
+        }
+
+        #impl_to_owned
+    }
+}
+
+/// The lifetime ref views borrow for, `'a` unless the original model already uses it
+fn ref_lifetime(generics: &syn::Generics) -> syn::Lifetime {
+    let taken: Vec<String> = generics.lifetimes().map(|l| l.lifetime.ident.to_string()).collect();
+    let mut name = String::from("a");
+    while taken.contains(&name) {
+        name.push('_');
+    }
+    syn::Lifetime::new(&format!("'{name}"), proc_macro2::Span::call_site())
+}
+
+/// Generates the fieldless kind of an enum, i.e. `EventKind` of `Event`, along with `Event::kind(&self)`
+pub fn impl_kind_model(
+    ast: &DeriveInput,
+    attr: &Attribute,
+    model_args: &ModelAttrArgs
+) -> TokenStream {
+    let syn::Data::Enum(data) = &ast.data else {
+        abort!(attr, "Kind models can only be derived for `enum`s")
+    };
+    let KindArgs { name, derive, rename } = KindArgs::parse(attr, model_args);
+    let original_name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    rename.validate(&model_args.extras.members);
+
+    // Derives every kind needs are skipped when given again, as deriving a trait twice conflicts
+    const DERIVES: [&str; 6] = ["Debug", "Clone", "Copy", "PartialEq", "Eq", "Hash"];
+    let derive: Vec<syn::Path> = derive
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| !p.segments.last().is_some_and(|s| DERIVES.contains(&s.ident.to_string().as_str())))
+        .collect();
+
+    // Kinds keep the variants of the original, renaming only changes the names they're displayed and parsed by
+    let variants: Vec<&Ident> = data.variants.iter().map(|v| &v.ident).collect();
+    let names: Vec<String> = variants.iter().map(|v| rename.rename(v).unraw().to_string()).collect();
+    let docs = data.variants.iter().map(|v| extract_docs(&v.attrs));
+
+    let error_name = format_ident!("{}ParseError", name);
+    let error_display = format!("`{{}}` isn't a variant of `{name}`");
+    let doc_string = format!("This is the (Kind) model of ['{original_name}'], holding only which variant a value is.");
+    let error_doc = format!("The error returned when parsing a [`{name}`] from a name that isn't one of its variants");
+    let kind_doc = format!("The [`{name}`] of this value, i.e. which variant it is");
+    quote! {
+        #[doc = #doc_string]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, #(#derive),*)]
+        pub enum #name {
+            #(#docs #variants),*
+        }
+
+        impl #name {
+            /// Every kind, in the order the variants are declared
+            pub const ALL: &'static [#name] = &[#(#name::#variants),*];
+
+            /// The name of the variant, as used by `Display` and `FromStr`
+            pub const fn as_str(&self) -> &'static str {
+                match *self {
+                    #(#name::#variants => #names),*
+                }
+            }
+        }
+
+        impl #impl_generics #original_name #ty_generics #where_clause {
+            #[doc = #kind_doc]
+            pub fn kind(&self) -> #name {
+                match *self {
+                    #(#original_name::#variants { .. } => #name::#variants),*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<&#original_name #ty_generics> for #name #where_clause {
+            fn from(value: &#original_name #ty_generics) -> Self {
+                value.kind()
+            }
+        }
+
+        impl ::core::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct #error_name {
+            /// The name that failed to parse
+            pub value: ::std::string::String,
+        }
 
-}
\ No newline at end of file
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, #error_display, self.value)
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        impl ::core::str::FromStr for #name {
+            type Err = #error_name;
+
+            fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+                match value {
+                    #(#names => ::core::result::Result::Ok(#name::#variants),)*
+                    _ => ::core::result::Result::Err(#error_name { value: value.to_string() }),
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#![allow(dead_code)]
+#![allow(dead_code, clippy::duplicated_attributes)]
 
 use poem_openapi::{payload::Json, types::MaybeUndefined, ApiResponse, Object};
 use restructed::Models;
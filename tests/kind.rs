@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+extern crate restructed;
+
+use restructed::Models;
+use std::collections::HashSet;
+
+#[derive(Models)]
+#[kind(EventKind)]
+enum Event<T> {
+    Created { id: u32, payload: T },
+    Deleted(u32),
+    Cleared,
+}
+
+#[derive(Models)]
+#[kind(StatusLabel, rename_all = "snake_case", derive(serde::Serialize, Debug))]
+enum Status<'a> {
+    /// Still running
+    InProgress(&'a str),
+    Done,
+}
+
+#[test]
+fn kind_of_variant() {
+    assert_eq!(Event::Created { id: 1, payload: "x" }.kind(), EventKind::Created);
+    assert_eq!(EventKind::from(&Event::<()>::Deleted(1)), EventKind::Deleted);
+    assert_eq!(EventKind::ALL, &[EventKind::Created, EventKind::Deleted, EventKind::Cleared]);
+
+    let kinds: HashSet<EventKind> = EventKind::ALL.iter().copied().collect();
+    assert_eq!(kinds.len(), 3);
+}
+
+#[test]
+fn kind_display_and_parse() {
+    assert_eq!(EventKind::Deleted.to_string(), "Deleted");
+    assert_eq!("Cleared".parse::<EventKind>(), Ok(EventKind::Cleared));
+
+    let error = "Updated".parse::<EventKind>().unwrap_err();
+    assert_eq!(error.to_string(), "`Updated` isn't a variant of `EventKind`");
+
+    // Renamed variants keep their identifiers, but are displayed and parsed by their new names
+    assert_eq!(Status::InProgress("build").kind(), StatusLabel::InProgress);
+    assert_eq!(StatusLabel::InProgress.as_str(), "in_progress");
+    assert_eq!(StatusLabel::InProgress.to_string(), "in_progress");
+    assert_eq!("done".parse::<StatusLabel>(), Ok(StatusLabel::Done));
+    assert!("Done".parse::<StatusLabel>().is_err());
+}
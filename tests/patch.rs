@@ -3,6 +3,7 @@ extern crate restructed;
 
 use poem_openapi::types::MaybeUndefined;
 use restructed::Models;
+use restructed_types::{json_patch::Operation, Maybe};
 use serde_json::{json, to_value};
 
 #[derive(Models, Clone)]
@@ -65,6 +66,595 @@ impl UserAlt {
     }
 }
 
+//------------------ Structs - Maybe
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(ProfileUpdate, omit(id), option = Maybe, derive(serde::Serialize, serde::Deserialize))]
+struct Profile {
+    id: i32,
+    display_name: String,
+    bio: Option<String>,
+}
+
+#[test]
+fn maybe_merge() {
+    let profile = Profile { id: 1, display_name: "Cool Doode".to_string(), bio: Some("Hi".to_string()) };
+
+    let update = ProfileUpdate { display_name: None, bio: Maybe::Undefined };
+    assert_eq!(update.merge(profile.clone()), profile);
+
+    let update = ProfileUpdate { display_name: None, bio: Maybe::Null };
+    assert_eq!(update.merge(profile.clone()).bio, None);
+
+    let update: ProfileUpdate = profile.into();
+    assert_eq!(update.bio, Maybe::Value("Hi".to_string()));
+}
+
+#[test]
+fn maybe_missing_and_null() {
+    let missing: ProfileUpdate = serde_json::from_value(json!({ "display_name": "Cooler Doode" })).unwrap();
+    assert_eq!(missing.bio, Maybe::Undefined);
+    assert_eq!(to_value(&missing).unwrap(), json!({ "display_name": "Cooler Doode" }));
+
+    let null: ProfileUpdate = serde_json::from_value(json!({ "bio": null })).unwrap();
+    assert_eq!(null.bio, Maybe::Null);
+    assert_eq!(to_value(&null).unwrap(), json!({ "display_name": null, "bio": null }));
+}
+
+//------------------ Structs - custom option wrappers
+
+/// Shaped like the wrappers of other crates (e.g. juniper's `Nullable`), used through `PatchOption`
+mod wrapper {
+    use restructed_types::{Maybe, PatchOption};
+
+    #[derive(Debug, PartialEq)]
+    pub enum Nullable<T> {
+        ImplicitNull,
+        ExplicitNull,
+        Some(T),
+    }
+
+    impl<T> PatchOption for Nullable<T> {
+        type Value = T;
+
+        fn undefined() -> Self {
+            Self::ImplicitNull
+        }
+
+        fn from_option(value: Option<T>) -> Self {
+            value.map_or(Self::ExplicitNull, Self::Some)
+        }
+
+        fn into_maybe(self) -> Maybe<T> {
+            match self {
+                Self::ImplicitNull => Maybe::Undefined,
+                Self::ExplicitNull => Maybe::Null,
+                Self::Some(v) => Maybe::Value(v),
+            }
+        }
+
+        fn as_maybe(&self) -> Maybe<&T> {
+            match self {
+                Self::ImplicitNull => Maybe::Undefined,
+                Self::ExplicitNull => Maybe::Null,
+                Self::Some(v) => Maybe::Value(v),
+            }
+        }
+
+        fn is_undefined(&self) -> bool {
+            matches!(self, Self::ImplicitNull)
+        }
+    }
+}
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(ProfileNullable, omit(id), option = wrapper::Nullable)]
+#[patch(ProfilePoem, omit(id), option = poem_openapi::types::MaybeUndefined, derive(serde::Deserialize))]
+#[patch(ProfileDoubled, omit(id), option = Option<Option<_>>, derive(serde::Deserialize))]
+#[patch(ProfileTyped, omit(id), option = restructed_types::Maybe<_>)]
+struct ProfileWrapped {
+    id: i32,
+    display_name: String,
+    bio: Option<String>,
+}
+
+#[test]
+fn custom_option_wrapper() {
+    let profile = ProfileWrapped { id: 1, display_name: "Cool Doode".to_string(), bio: Some("Hi".to_string()) };
+
+    let update: ProfileNullable = profile.clone().into();
+    assert_eq!(update.bio, wrapper::Nullable::Some("Hi".to_string()));
+
+    let update = ProfileNullable { display_name: None, bio: wrapper::Nullable::ImplicitNull };
+    assert_eq!(update.merge(profile.clone()), profile);
+
+    let update = ProfileNullable { display_name: None, bio: wrapper::Nullable::ExplicitNull };
+    assert_eq!(update.merge(profile.clone()).bio, None);
+
+    let update: ProfilePoem = serde_json::from_value(json!({ "bio": null })).unwrap();
+    assert!(update.bio.is_null());
+    assert_eq!(update.merge(profile.clone()).bio, None);
+}
+
+#[test]
+fn option_wrapper_types() {
+    let profile = ProfileWrapped { id: 1, display_name: "Cool Doode".to_string(), bio: Some("Hi".to_string()) };
+
+    // The wrapper is given as the type of the field, with `_` in place of the original's
+    let update = ProfileDoubled { display_name: None, bio: Some(None) };
+    assert_eq!(update.merge(profile.clone()).bio, None);
+    #[cfg(feature = "serde")]
+    {
+        let update: ProfileDoubled = serde_json::from_value(json!({ "bio": null })).unwrap();
+        assert_eq!(update.bio, Some(None));
+    }
+
+    let update = ProfileTyped { display_name: None, bio: Maybe::Value("Hey".to_string()) };
+    assert_eq!(update.merge(profile.clone()).bio.as_deref(), Some("Hey"));
+    assert_eq!(ProfileTyped::from(profile).bio, Maybe::Value("Hi".to_string()));
+}
+
+//------------------ Structs -- introspection
+
+#[test]
+fn patch_introspection() {
+    let empty = UserUpdatables::empty();
+    assert!(empty.is_empty());
+    assert_eq!(empty.len(), 0);
+    assert!(UserMaybes::empty().is_empty());
+
+    let update = UserMaybes {
+        display_name: Some("Cooler doode".to_string()),
+        bio: MaybeUndefined::Null,
+        password: None,
+    };
+    assert!(!update.is_empty());
+    assert_eq!(update.len(), 2);
+    assert_eq!(update.changed_fields(), vec!["display_name", "bio"]);
+
+    // Fields are named as they are on the original, even when renamed on the patch
+    let update = UserRenamePatch { hash: Some("new hash".to_string()), ..UserRenamePatch::empty() };
+    assert_eq!(update.changed_fields(), vec!["password_hash"]);
+
+    let update = PointPatch(None, Some(None));
+    assert_eq!(update.changed_fields(), vec!["2"]);
+
+    let update = ProfileNullable { bio: wrapper::Nullable::ExplicitNull, ..ProfileNullable::empty() };
+    assert_eq!(update.changed_fields(), vec!["bio"]);
+}
+
+#[derive(Models)]
+#[patch(SettingsPatch)]
+struct Settings {
+    theme: String,
+    language: String,
+}
+
+// Patches leave `Default` to their own impls (or `derive(Default)`), only `empty` is generated
+impl Default for SettingsPatch {
+    fn default() -> Self {
+        Self { theme: Some("dark".to_string()), ..Self::empty() }
+    }
+}
+
+#[test]
+fn patch_manual_default() {
+    assert_eq!(SettingsPatch::default().changed_fields(), vec!["theme"]);
+    assert!(SettingsPatch::empty().is_empty());
+}
+
+//------------------ Structs -- diff
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(ArticleDiff, omit(id), diff = true)]
+#[patch(ArticleMaybeDiff, omit(id), diff = true, option = MaybeUndefined)]
+struct Article<T> {
+    id: i32,
+    title: String,
+    summary: Option<String>,
+    tags: Vec<T>,
+}
+
+#[test]
+fn diff_between_originals() {
+    let old = Article { id: 1, title: "Cool".to_string(), summary: Some("Hi".to_string()), tags: vec![1] };
+    let new = Article { id: 1, title: "Cool".to_string(), summary: None, tags: vec![1, 2] };
+
+    let diff = ArticleDiff::diff(&old, &new);
+    assert_eq!(diff.changed_fields(), vec!["summary", "tags"]);
+    assert_eq!(diff.summary, Some(None));
+    assert_eq!(diff.merge(old.clone()), new);
+
+    // Fields that became `None` are cleared with `Null`
+    let diff = ArticleMaybeDiff::diff(&old, &new);
+    assert!(diff.summary.is_null());
+    assert_eq!(diff.merge(old.clone()), new);
+
+    assert!(ArticleDiff::diff(&old, &old).is_empty());
+}
+
+//------------------ Structs -- merge_tracked
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(AccountUpdate, omit(id), merge_tracked = true, option = MaybeUndefined)]
+struct UserAccount {
+    id: i32,
+    email: String,
+    name: String,
+    bio: Option<String>,
+}
+
+#[test]
+fn merge_tracked_changes() {
+    let mut account = UserAccount { id: 1, email: "a@x".to_string(), name: "Cool".to_string(), bio: None };
+
+    let update = AccountUpdate {
+        email: Some("b@y".to_string()),
+        name: Some("Cool".to_string()), // Same value, so not a change
+        bio: MaybeUndefined::Value("Hi".to_string()),
+    };
+    let changes = update.merge_tracked(&mut account);
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0], AccountUpdateChange { field: "email", old: r#""a@x""#.to_string(), new: r#""b@y""#.to_string() });
+    assert_eq!(changes[0].to_string(), r#"`email` changed from "a@x" to "b@y""#);
+    assert_eq!(changes[1].field, "bio");
+    assert_eq!(account.email, "b@y");
+    assert_eq!(account.bio.as_deref(), Some("Hi"));
+
+    assert!(AccountUpdate::empty().merge_tracked(&mut account).is_empty());
+}
+
+//------------------ Structs -- JSON Merge Patch
+
+// `merge_patch` and `json_patch` need the `serde` feature, as do the nested patches converting merge patch documents
+#[cfg(feature = "serde")]
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(ContactPatch, omit(id), merge_patch = true, json_patch = true, derive(Clone, Debug))]
+#[patch(ContactMaybePatch, omit(id), merge_patch = true, option = MaybeUndefined, rename(phone => mobile))]
+struct Contact {
+    id: i32,
+    email: String,
+    phone: Option<String>,
+    tags: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn merge_patch_documents() {
+    let contact = Contact { id: 1, email: "a@x".to_string(), phone: Some("123".to_string()), tags: vec![] };
+
+    let patch = ContactPatch::from_merge_patch(&json!({ "email": "b@y", "phone": null })).unwrap();
+    assert_eq!(patch.changed_fields(), vec!["email", "phone"]);
+    let updated = patch.clone().merge(contact.clone());
+    assert_eq!((updated.email.as_str(), updated.phone), ("b@y", None));
+    assert_eq!(patch.to_merge_patch().unwrap(), json!({ "email": "b@y", "phone": null }));
+
+    let patch = ContactMaybePatch::from_merge_patch(&json!({ "mobile": null, "tags": ["a"] })).unwrap();
+    assert!(patch.mobile.is_null());
+    assert_eq!(patch.to_merge_patch().unwrap(), json!({ "mobile": null, "tags": ["a"] }));
+    assert_eq!(ContactMaybePatch::from_merge_patch(&json!({})).unwrap().to_merge_patch().unwrap(), json!({}));
+}
+
+#[cfg(feature = "serde")]
+#[derive(Models)]
+#[patch(PreferencesPatch, attributes_with = "all", merge_patch = true, json_patch = true)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Preferences {
+    time_zone: String,
+    dark_mode: Option<bool>,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn merge_patch_serde_keys() {
+    // The keys are the same as the patch's own serde names, renamed by the `rename_all` it carries
+    let document = json!({ "timeZone": "UTC", "darkMode": null });
+    let patch = PreferencesPatch::from_merge_patch(&document).unwrap();
+    assert_eq!((patch.time_zone.as_deref(), patch.dark_mode), (Some("UTC"), Some(None)));
+    assert_eq!(patch.to_merge_patch().unwrap(), document);
+    assert_eq!(to_value(&patch).unwrap(), document);
+
+    let error = PreferencesPatch::from_merge_patch(&json!({ "time_zone": "UTC" })).unwrap_err();
+    assert_eq!(error, PreferencesPatchMergePatchError::UnknownField("time_zone".to_string()));
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Postal {
+    city: String,
+    zip: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Models)]
+#[patch(ShipmentPatch, omit(id), merge_patch = true)]
+struct Shipment {
+    id: i32,
+    address: Option<Postal>,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn merge_patch_nested_documents() {
+    let document = json!({ "address": { "city": "Leeds", "zip": null } });
+    let patch = ShipmentPatch::from_merge_patch(&document).unwrap();
+    assert_eq!(patch.address, Some(Some(Postal { city: "Leeds".to_string(), zip: None })));
+    assert_eq!(patch.to_merge_patch().unwrap(), document);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn merge_patch_validation() {
+    let error = ContactPatch::from_merge_patch(&json!({ "id": 2 })).unwrap_err();
+    assert_eq!(error, ContactPatchMergePatchError::UnknownField("id".to_string()));
+
+    let error = ContactPatch::from_merge_patch(&json!({ "email": null })).unwrap_err();
+    assert_eq!(error, ContactPatchMergePatchError::NullNotAllowed("email"));
+    assert_eq!(error.to_string(), "field `email` can't be null");
+
+    let error = ContactPatch::from_merge_patch(&json!({ "tags": "a" })).unwrap_err();
+    assert!(matches!(error, ContactPatchMergePatchError::InvalidValue { field: "tags", .. }));
+
+    assert_eq!(ContactPatch::from_merge_patch(&json!([])).unwrap_err(), ContactPatchMergePatchError::NotAnObject);
+}
+
+//------------------ Structs -- JSON Patch
+
+#[cfg(feature = "serde")]
+fn operations(document: serde_json::Value) -> Vec<Operation> {
+    serde_json::from_value(document).unwrap()
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_patch_operations() {
+    let contact = Contact { id: 1, email: "a@x".to_string(), phone: Some("123".to_string()), tags: vec![] };
+
+    let patch = ContactPatch::from_json_patch(&operations(json!([
+        { "op": "test", "path": "/email", "value": "a@x" },
+        { "op": "replace", "path": "/email", "value": "b@y" },
+        { "op": "remove", "path": "/phone" },
+        { "op": "add", "path": "/tags", "value": ["a"] },
+    ])))
+    .unwrap();
+    assert_eq!(patch.patch.changed_fields(), vec!["email", "phone", "tags"]);
+    assert_eq!(patch.test.changed_fields(), vec!["email"]);
+    let updated = patch.merge(contact.clone()).unwrap();
+    assert_eq!((updated.email.as_str(), updated.phone, updated.tags), ("b@y", None, vec!["a".to_string()]));
+
+    let patch = ContactPatch::from_json_patch(&operations(json!([
+        { "op": "test", "path": "/phone", "value": null },
+        { "op": "replace", "path": "/email", "value": "b@y" },
+    ])))
+    .unwrap();
+    let mut unchanged = contact.clone();
+    assert_eq!(patch.merge_mut(&mut unchanged).unwrap_err(), ContactPatchJsonPatchError::TestFailed("phone"));
+    assert_eq!(unchanged, contact);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_patch_validation() {
+    let error = |document| ContactPatch::from_json_patch(&operations(document)).err().unwrap();
+
+    let error_move = error(json!([{ "op": "move", "from": "/email", "path": "/phone" }]));
+    assert_eq!(error_move, ContactPatchJsonPatchError::UnsupportedOperation("move"));
+    assert_eq!(error_move.to_string(), "unsupported operation `move`");
+    assert_eq!(error(json!([{ "op": "replace", "path": "/id", "value": 2 }])), ContactPatchJsonPatchError::UnknownField("id".to_string()));
+    assert_eq!(error(json!([{ "op": "add", "path": "/tags/0", "value": "a" }])), ContactPatchJsonPatchError::InvalidPath("/tags/0".to_string()));
+    assert_eq!(error(json!([{ "op": "remove", "path": "/email" }])), ContactPatchJsonPatchError::RemoveNotAllowed("email"));
+    assert_eq!(error(json!([{ "op": "replace", "path": "/email", "value": null }])), ContactPatchJsonPatchError::NullNotAllowed("email"));
+    assert!(matches!(
+        error(json!([{ "op": "test", "path": "/tags", "value": 1 }])),
+        ContactPatchJsonPatchError::InvalidValue { field: "tags", .. }
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_patch_serde_keys() {
+    let patch = PreferencesPatch::from_json_patch(&operations(json!([
+        { "op": "test", "path": "/timeZone", "value": "UTC" },
+        { "op": "remove", "path": "/darkMode" },
+    ])))
+    .unwrap();
+    let preferences = Preferences { time_zone: "UTC".to_string(), dark_mode: Some(true) };
+    assert_eq!(patch.merge(preferences).unwrap().dark_mode, None);
+
+    let error = PreferencesPatch::from_json_patch(&operations(json!([{ "op": "remove", "path": "/dark_mode" }])));
+    assert_eq!(error.err().unwrap(), PreferencesPatchJsonPatchError::UnknownField("dark_mode".to_string()));
+}
+
+// Only merging with `test` operations compares the fields, so the patch itself merges without `PartialEq`
+#[cfg(feature = "serde")]
+#[derive(Models)]
+#[patch(LabelPatch, json_patch = true)]
+struct Label<T> {
+    text: String,
+    data: T,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Opaque(u8);
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_patch_without_partial_eq() {
+    let patch = LabelPatch::<Opaque>::from_json_patch(&operations(json!([{ "op": "replace", "path": "/data", "value": 2 }])));
+    let label = patch.ok().unwrap().patch.merge(Label { text: "a".to_string(), data: Opaque(1) });
+    assert_eq!((label.text.as_str(), label.data.0), ("a", 2));
+}
+
+//------------------ Structs -- nested patches
+
+#[cfg(feature = "serde")]
+#[derive(Models, Clone, Debug, Default, PartialEq)]
+#[patch(PlacePatch, merge_patch = true)]
+struct Place {
+    city: String,
+    zip: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(CustomerPatch, omit(id), nested(address = PlacePatch, billing = PlacePatch), merge_patch = true, diff = true)]
+#[patch(CustomerStrictPatch, omit(id), nested(address = PlacePatch, billing = PlacePatch), nested_none = "error", option = MaybeUndefined)]
+struct Customer {
+    id: i32,
+    address: Place,
+    billing: Option<Place>,
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn nested_patch_merge() {
+    let place = Place { city: "Leeds".to_string(), zip: Some("LS1".to_string()) };
+    let customer = Customer { id: 1, address: place.clone(), billing: None };
+
+    let patch = CustomerPatch {
+        address: Some(PlacePatch { city: Some("York".to_string()), zip: None }),
+        billing: Some(Some(PlacePatch { city: Some("Hull".to_string()), zip: None })),
+    };
+    let updated = patch.merge(customer.clone());
+    assert_eq!(updated.address, Place { city: "York".to_string(), zip: Some("LS1".to_string()) });
+    assert_eq!(updated.billing, Some(Place { city: "Hull".to_string(), zip: None }));
+
+    let patch = CustomerPatch { billing: Some(None), ..CustomerPatch::empty() };
+    assert_eq!(patch.merge(updated).billing, None);
+
+    let diff = CustomerPatch::diff(&customer, &Customer { billing: Some(place.clone()), ..customer.clone() });
+    assert_eq!(diff.changed_fields(), vec!["billing"]);
+    assert_eq!(diff.billing.unwrap().unwrap().city, Some("Leeds".to_string()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn nested_patch_on_none() {
+    let customer = Customer { id: 1, address: Place::default(), billing: None };
+
+    let patch = CustomerStrictPatch {
+        address: Some(PlacePatch { city: Some("York".to_string()), zip: None }),
+        billing: MaybeUndefined::Value(PlacePatch { city: Some("Hull".to_string()), zip: None }),
+    };
+    let mut unchanged = customer.clone();
+    let error = patch.merge_mut(&mut unchanged).unwrap_err();
+    assert_eq!(error, CustomerStrictPatchMergeError { field: "billing" });
+    assert_eq!(error.to_string(), "can't merge the nested patch of `billing` as the field is `None`");
+    assert_eq!(unchanged, customer);
+
+    let patch = CustomerStrictPatch { billing: MaybeUndefined::Null, ..CustomerStrictPatch::empty() };
+    assert_eq!(patch.merge(customer.clone()).unwrap(), customer);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn nested_merge_patch_documents() {
+    let document = json!({ "address": { "zip": null }, "billing": { "city": "Hull" } });
+    let patch = CustomerPatch::from_merge_patch(&document).unwrap();
+    assert_eq!(patch.address.as_ref().unwrap().changed_fields(), vec!["zip"]);
+    assert_eq!(patch.to_merge_patch().unwrap(), document);
+
+    let customer = Customer { id: 1, address: Place { city: "Leeds".to_string(), zip: Some("LS1".to_string()) }, billing: None };
+    let updated = patch.merge(customer);
+    assert_eq!(updated.address, Place { city: "Leeds".to_string(), zip: None });
+    assert_eq!(updated.billing, Some(Place { city: "Hull".to_string(), zip: None }));
+
+    let error = CustomerPatch::from_merge_patch(&json!({ "address": { "country": "UK" } })).err().unwrap();
+    assert_eq!(error.to_string(), "invalid value for field `address`: unknown field `country`");
+}
+
+//------------------ Structs -- collections
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(PostPatch, omit(id), collections(tags, roles, scores), derive(Debug, PartialEq, serde::Serialize, serde::Deserialize))]
+struct Post {
+    id: i32,
+    title: String,
+    tags: Vec<String>,
+    roles: std::collections::BTreeSet<String>,
+    scores: std::collections::HashMap<String, u32>,
+}
+
+#[test]
+fn collection_operations() {
+    let post = Post {
+        id: 1,
+        title: "Hello".to_string(),
+        tags: vec!["a".to_string(), "b".to_string()],
+        roles: ["admin".to_string(), "editor".to_string()].into(),
+        scores: [("a".to_string(), 1), ("b".to_string(), 2)].into(),
+    };
+
+    let patch = PostPatch {
+        title: None,
+        tags: vec![PostPatchTagsOp::Append(vec!["c".to_string()])],
+        roles: vec![PostPatchRolesOp::Retain(["editor".to_string()].into())],
+        scores: vec![PostPatchScoresOp::Remove(vec!["a".to_string()])],
+    };
+    let mut updated = patch.merge(post.clone());
+    assert_eq!(updated.tags, vec!["a", "b", "c"]);
+    assert_eq!(updated.roles, ["editor".to_string()].into());
+    assert_eq!(updated.scores, [("b".to_string(), 2)].into());
+
+    let patch = PostPatch {
+        title: Some("Bye".to_string()),
+        // Operations are applied in order
+        tags: vec![
+            PostPatchTagsOp::Remove(vec!["a".to_string(), "c".to_string()]),
+            PostPatchTagsOp::Append(vec!["d".to_string()]),
+        ],
+        roles: vec![PostPatchRolesOp::Clear],
+        scores: vec![PostPatchScoresOp::Clear, PostPatchScoresOp::Insert([("b".to_string(), 3), ("c".to_string(), 4)].into())],
+    };
+    patch.merge_mut(&mut updated);
+    assert_eq!(updated.title, "Bye");
+    assert_eq!(updated.tags, vec!["b", "d"]);
+    assert!(updated.roles.is_empty());
+    assert_eq!(updated.scores, [("b".to_string(), 3), ("c".to_string(), 4)].into());
+
+    // Collections without an operation are left as they are, and patches from an original set them whole
+    PostPatch { title: None, tags: vec![], roles: vec![], scores: vec![PostPatchScoresOp::Clear] }.merge_mut(&mut updated);
+    assert_eq!((updated.tags.len(), updated.scores.len()), (2, 0));
+
+    let patch: PostPatch = post.clone().into();
+    assert_eq!(patch.tags, vec![PostPatchTagsOp::Set(post.tags.clone())]);
+    assert_eq!(patch.merge(updated), post);
+}
+
+#[test]
+fn collection_operations_serde() {
+    let patch: PostPatch = serde_json::from_value(json!({
+        "title": null,
+        "tags": [{ "append": ["c"] }, { "remove": ["a"] }],
+        "roles": ["clear"],
+    }))
+    .unwrap();
+    assert_eq!(patch.tags, vec![PostPatchTagsOp::Append(vec!["c".to_string()]), PostPatchTagsOp::Remove(vec!["a".to_string()])]);
+    assert_eq!(patch.roles, vec![PostPatchRolesOp::Clear]);
+    assert!(patch.scores.is_empty());
+    assert_eq!(to_value(&patch).unwrap(), json!({ "title": null, "tags": [{ "append": ["c"] }, { "remove": ["a"] }], "roles": ["clear"] }));
+}
+
+// The operations only need what comparing the elements takes, so generic collections work too
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(BagPatch, collections(items, keys))]
+struct Bag<T, K: Ord> {
+    items: Vec<T>,
+    keys: std::collections::BTreeSet<K>,
+}
+
+#[test]
+fn collection_operations_generic() {
+    let bag = Bag { items: vec![1, 2, 3], keys: ["a"].into() };
+    let patch = BagPatch {
+        items: vec![BagPatchItemsOp::Append(vec![4]), BagPatchItemsOp::Remove(vec![1, 3])],
+        keys: vec![BagPatchKeysOp::Append(["b"].into())],
+    };
+    assert_eq!(patch.merge(bag), Bag { items: vec![2, 4], keys: ["a", "b"].into() });
+}
+
 //------------------ Structs -- defaults
 
 #[derive(Models)]
@@ -122,6 +712,7 @@ fn alt_omitted_only() {
 
 #[derive(restructed::Models)]
 #[patch(UserPatch, attributes_with = "all". skip_serializing_double_option = true)]
+#[patch(UserPatchPlain, attributes_with = "all")]
 #[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone)]
 struct UserDontSerialName {
     name: Option<String>,
@@ -147,4 +738,276 @@ fn name_patch_serialization() {
     // When name is None, it should not serialize the field
     let value_none = to_value(&name_none).unwrap();
     assert!(value_none.get("name").is_none());
-}
\ No newline at end of file
+}
+#[cfg(feature = "serde")]
+#[test]
+fn double_option_null_and_missing() {
+    let missing: UserPatch = serde_json::from_value(json!({ "email": "www.com" })).unwrap();
+    assert_eq!(missing.name, None);
+
+    // `null` clears the field rather than leaving it unchanged
+    let null: UserPatch = serde_json::from_value(json!({ "name": null })).unwrap();
+    assert_eq!(null.name, Some(None));
+    assert_eq!(null.email, None);
+
+    let user = UserDontSerialName { name: Some("c00l dud3".to_string()), email: "www.com".to_string() };
+    assert_eq!(null.clone().merge(user.clone()).name, None);
+    assert_eq!(missing.clone().merge(user).name.as_deref(), Some("c00l dud3"));
+
+    for patch in [missing, null] {
+        let round_trip: UserPatch = serde_json::from_value(to_value(&patch).unwrap()).unwrap();
+        assert_eq!(round_trip, patch);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn double_option_round_trip() {
+    // Unset fields are skipped even without `skip_serializing_double_option`, as `null` would clear them
+    let unset = UserPatchPlain { name: None, email: Some("www.com".to_string()) };
+    assert_eq!(to_value(&unset).unwrap(), json!({ "email": "www.com" }));
+
+    let cleared = UserPatchPlain { name: Some(None), email: None };
+    assert_eq!(to_value(&cleared).unwrap(), json!({ "name": null, "email": null }));
+
+    let user = UserDontSerialName { name: Some("c00l dud3".to_string()), email: "www.com".to_string() };
+    for patch in [unset, cleared] {
+        let round_trip: UserPatchPlain = serde_json::from_value(to_value(&patch).unwrap()).unwrap();
+        assert_eq!(round_trip, patch);
+        assert_eq!(round_trip.merge(user.clone()).name, patch.merge(user.clone()).name);
+    }
+}
+
+//------------------ Structs -- generics
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(PageUpdate, omit(meta))]
+struct Page<'a, T, M>
+where
+    T: Clone,
+{
+    title: &'a str,
+    items: Vec<T>,
+    note: Option<T>,
+    meta: M,
+}
+
+#[test]
+fn generics_carried_and_pruned() {
+    let page = Page {
+        title: "Cool Page",
+        items: vec![1, 2],
+        note: None,
+        meta: (),
+    };
+
+    let update: PageUpdate<'_, i32> = PageUpdate {
+        title: None,
+        items: Some(vec![3]),
+        note: Some(Some(4)),
+    };
+
+    let updated = update.merge(page.clone());
+    assert_eq!(updated.title, page.title);
+    assert_eq!(updated.items, vec![3]);
+    assert_eq!(updated.note, Some(4));
+
+    let from: PageUpdate<i32> = page.into();
+    assert_eq!(from.title, Some("Cool Page"));
+}
+
+//------------------ Tuple Structs
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(PointPatch, omit(1))]
+struct Point(f64, f64, Option<f64>);
+
+#[test]
+fn tuple_struct_positions() {
+    let point = Point(1.0, 2.0, None);
+
+    let patch = PointPatch(None, Some(Some(4.0)));
+    assert_eq!(patch.merge(point.clone()), Point(1.0, 2.0, Some(4.0)));
+
+    let from: PointPatch = point.into();
+    assert_eq!(from.0, Some(1.0));
+    assert_eq!(from.1, Some(None));
+}
+
+//------------------ Structs -- field level attributes
+
+#[derive(Models, Clone)]
+#[patch(UserUpdate)]
+#[patch(AdminUpdate)]
+#[derive(Debug)]
+struct UserFieldAttrs {
+    #[patch(skip(UserUpdate, AdminUpdate))]
+    id: i32,
+    display_name: String,
+    #[patch(only(AdminUpdate))]
+    role: String,
+}
+
+#[test]
+fn field_level_attributes() {
+    let user = UserFieldAttrs {
+        id: 1,
+        display_name: "Cool Doode".to_string(),
+        role: "user".to_string(),
+    };
+
+    let UserUpdate { display_name } = user.clone().into();
+    assert_eq!(display_name.as_deref(), Some("Cool Doode"));
+
+    let update = AdminUpdate {
+        display_name: None,
+        role: Some("admin".to_string()),
+    };
+    let updated = update.merge(user);
+    assert_eq!(updated.id, 1);
+    assert_eq!(updated.role, "admin");
+}
+
+//------------------ Structs -- rename
+
+#[derive(Models, Clone, Debug)]
+#[patch(UserRenamePatch, omit(id), rename(password_hash => hash), option = MaybeUndefined)]
+#[patch(UserRenameCamel, omit(id), rename_all = "camelCase")]
+struct UserRename {
+    id: i32,
+    display_name: String,
+    password_hash: String,
+    bio: Option<String>,
+}
+
+#[test]
+fn renamed_fields() {
+    let user = UserRename {
+        id: 1,
+        display_name: "Cool Doode".to_string(),
+        password_hash: "hash".to_string(),
+        bio: None,
+    };
+
+    let patch: UserRenamePatch = user.clone().into();
+    assert_eq!(patch.hash.as_deref(), Some("hash"));
+
+    let patch = UserRenamePatch {
+        display_name: None,
+        hash: Some("new hash".to_string()),
+        bio: MaybeUndefined::Value("bio".to_string()),
+    };
+    let updated = patch.merge(user.clone());
+    assert_eq!(updated.password_hash, "new hash");
+    assert_eq!(updated.bio.as_deref(), Some("bio"));
+
+    let camel = UserRenameCamel {
+        displayName: Some("Cooler Doode".to_string()),
+        passwordHash: None,
+        bio: None,
+    };
+    assert_eq!(camel.merge(user).display_name, "Cooler Doode");
+}
+
+#[derive(Models, Clone, Debug)]
+#[patch(TokenCamel, rename_all = "camelCase")]
+struct Token {
+    r#type: String,
+    as_: String,
+}
+
+#[test]
+fn renamed_raw_fields() {
+    let token = Token { r#type: "bearer".to_string(), as_: "user".to_string() };
+
+    // Keywords stay raw identifiers, whether they were raw before or only became keywords by renaming
+    let patch = TokenCamel { r#type: Some("basic".to_string()), r#as: None };
+    let token = patch.merge(token);
+    assert_eq!((token.r#type.as_str(), token.as_.as_str()), ("basic", "user"));
+}
+
+//------------------ Enums
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(PaymentPatch)]
+#[patch(PaymentReplace, omit(Cash), on_mismatch = "replace", option = MaybeUndefined)]
+enum Payment {
+    Card { last4: u16, expiry: u32, nickname: Option<String> },
+    Bank { iban: String },
+    Cash(u32, u32),
+    Free,
+}
+
+#[test]
+fn enum_merge_matching_variant() {
+    let card = Payment::Card { last4: 1234, expiry: 1, nickname: None };
+
+    let patch = PaymentPatch::Card { last4: None, expiry: Some(2), nickname: Some(Some("work".to_string())) };
+    let updated = patch.merge(card).unwrap();
+    assert_eq!(updated, Payment::Card { last4: 1234, expiry: 2, nickname: Some("work".to_string()) });
+
+    let mut cash = Payment::Cash(1, 2);
+    PaymentPatch::Cash(None, Some(5)).merge_mut(&mut cash).unwrap();
+    assert_eq!(cash, Payment::Cash(1, 5));
+
+    let patch: PaymentPatch = Payment::Bank { iban: "GB00".to_string() }.into();
+    assert!(matches!(patch, PaymentPatch::Bank { iban: Some(ref v) } if v == "GB00"));
+}
+
+#[test]
+fn enum_merge_mismatch_error() {
+    let mut bank = Payment::Bank { iban: "GB00".to_string() };
+    let error = PaymentPatch::Free.merge_mut(&mut bank).unwrap_err();
+    assert_eq!(error, PaymentPatchMergeError { patch_variant: "Free", original_variant: "Bank" });
+    assert_eq!(error.to_string(), "can't merge variant `Free` of `PaymentPatch` into variant `Bank` of `Payment`");
+    assert_eq!(bank, Payment::Bank { iban: "GB00".to_string() });
+}
+
+#[test]
+fn enum_merge_mismatch_replace() {
+    let patch = PaymentReplace::Card {
+        last4: Some(1234),
+        expiry: Some(1),
+        nickname: MaybeUndefined::Null,
+    };
+    let replaced = patch.merge(Payment::Free).unwrap();
+    assert_eq!(replaced, Payment::Card { last4: 1234, expiry: 1, nickname: None });
+
+    // Every field of the variant is needed to replace the original
+    let patch = PaymentReplace::Card {
+        last4: Some(1234),
+        expiry: None,
+        nickname: MaybeUndefined::Null,
+    };
+    let error = patch.merge(Payment::Free).unwrap_err();
+    assert_eq!((error.patch_variant, error.original_variant), ("Card", "Free"));
+
+    assert_eq!(PaymentReplace::Free.merge(Payment::Cash(1, 2)).unwrap(), Payment::Free);
+
+    let rejected = PaymentReplace::try_from(Payment::Cash(1, 2)).err().unwrap();
+    assert_eq!(rejected.variant, "Cash");
+    assert_eq!(rejected.value, Payment::Cash(1, 2));
+}
+
+#[derive(Models, Clone, Debug, PartialEq)]
+#[patch(AccountPatch, omit(Card::cvv, Bank::iban))]
+enum Account {
+    Card { last4: u16, cvv: u16 },
+    Bank {
+        iban: String,
+        #[patch(skip(AccountPatch))]
+        owner: String,
+        nickname: String,
+    },
+}
+
+#[test]
+fn enum_variant_fields() {
+    let mut card = Account::Card { last4: 1234, cvv: 999 };
+    AccountPatch::Card { last4: Some(4321) }.merge_mut(&mut card).unwrap();
+    assert_eq!(card, Account::Card { last4: 4321, cvv: 999 });
+
+    let bank = Account::Bank { iban: "GB00".to_string(), owner: "Doode".to_string(), nickname: "main".to_string() };
+    let patch: AccountPatch = bank.into();
+    assert!(matches!(patch, AccountPatch::Bank { nickname: Some(ref v) } if v == "main"));
+}
@@ -126,3 +126,467 @@ fn mapping_enum() {
     let is_match = matches!(err.clone(), ApiError::NotFound(v) if v == "No User");
     assert!(is_match, "Expected NotFound, got {:?}", err);
 }
+
+//------------------ Generics
+
+#[derive(Models)]
+#[view(PageSummary, omit(items))]
+#[view(PageItems, fields(items))]
+struct Page<T: Clone, const N: usize>
+where
+    T: core::fmt::Debug,
+{
+    items: Vec<T>,
+    offsets: [u32; N],
+    total: usize,
+}
+
+#[derive(Models)]
+#[view(BorrowedName, fields(name))]
+struct Borrowed<'a, 'b> {
+    name: &'a str,
+    bio: &'b str,
+}
+
+#[test]
+fn generics_carried_and_pruned() {
+    let page = Page {
+        items: vec!["a", "b"],
+        offsets: [0, 1],
+        total: 2,
+    };
+
+    let summary: PageSummary<2> = Page { items: vec![1], offsets: [0, 1], total: 1 }.into();
+    assert_eq!(summary.total, 1);
+    assert_eq!(summary.offsets, [0, 1]);
+
+    let items: PageItems<&str> = page.into();
+    assert_eq!(items.items, vec!["a", "b"]);
+
+    let name: BorrowedName = Borrowed { name: "Nex", bio: "Rust" }.into();
+    assert_eq!(name.name, "Nex");
+}
+
+#[derive(Debug, Models)]
+#[view(ResultOk, fields(Ok))]
+enum GenericResult<T, E> {
+    Ok(T),
+    Err(E),
+}
+
+#[test]
+fn generic_enum() {
+    let ok: ResultOk<i32> = GenericResult::<i32, String>::Ok(1).try_into().unwrap();
+    let original: GenericResult<i32, String> = ok.into();
+    assert!(matches!(original, GenericResult::Ok(1)));
+}
+
+//------------------ Tuple Structs
+
+#[derive(Models, Clone)]
+#[view(PointXZ, fields(0, 2), derive(Debug, PartialEq))]
+#[view(PointYZ, omit(0))]
+struct Point(f64, pub f64, f64);
+
+#[derive(Models)]
+#[view(UserIdView)]
+struct UserId(u64);
+
+#[test]
+fn tuple_struct_positions() {
+    let point = Point(1.0, 2.0, 3.0);
+
+    let xz: PointXZ = point.clone().into();
+    assert_eq!(xz, PointXZ(1.0, 3.0));
+
+    let yz: PointYZ = point.into();
+    assert_eq!(yz.0, 2.0);
+    assert_eq!(yz.1, 3.0);
+
+    let id: UserIdView = UserId(7).into();
+    assert_eq!(id.0, 7);
+}
+
+//------------------ Structs -- field level attributes
+
+#[derive(Models)]
+#[view(UserPublic)]
+#[view(UserAdmin, omit(id))]
+#[view(UserAudit, fields(id))]
+#[view(UserDeriveless, attributes_with = "deriveless")] // field level attributes are never carried over
+struct UserFieldAttrs {
+    id: i32,
+    display_name: String,
+    #[view(skip(UserPublic))]
+    email: String,
+    #[view(only(UserAudit))]
+    password: String,
+}
+
+#[test]
+fn field_level_attributes() {
+    let user = || UserFieldAttrs {
+        id: 1,
+        display_name: "Cool Doode".to_string(),
+        email: "cool@doode.com".to_string(),
+        password: "Pls don't hack me".to_string(),
+    };
+
+    // `email` and `password` would be a compile error if present
+    let UserPublic { id, display_name } = user().into();
+    assert_eq!((id, display_name.as_str()), (1, "Cool Doode"));
+
+    let UserAdmin { display_name, email } = user().into();
+    assert_eq!((display_name.as_str(), email.as_str()), ("Cool Doode", "cool@doode.com"));
+
+    // `only` includes the field even though it isn't listed in `fields`
+    let UserAudit { id, password } = user().into();
+    assert_eq!((id, password.as_str()), (1, "Pls don't hack me"));
+}
+
+#[derive(Debug, Models)]
+#[view(ApiErrorPublic)]
+pub enum ApiErrorFieldAttrs {
+    NotFound(String),
+    #[view(skip(ApiErrorPublic))]
+    Internal(String),
+}
+
+#[test]
+fn variant_level_attributes() {
+    let internal = ApiErrorFieldAttrs::Internal("db down".to_string());
+    assert!(ApiErrorPublic::try_from(internal).is_err());
+}
+
+#[test]
+fn enum_view_try_from_error() {
+    let error = ApiErrorPublic::try_from(ApiErrorFieldAttrs::Internal("db down".to_string())).err().unwrap();
+    assert_eq!(error.variant, "Internal");
+    assert!(matches!(error.value, ApiErrorFieldAttrs::Internal(ref v) if v == "db down"));
+    assert_eq!(error.to_string(), "variant `Internal` of `ApiErrorFieldAttrs` isn't part of the view `ApiErrorPublic`");
+
+    let error: Box<dyn std::error::Error> = Box::new(ResultOk::try_from(GenericResult::<i32, String>::Err("no".to_string())).err().unwrap());
+    assert_eq!(format!("{error:?}"), r#"ResultOkTryFromError { variant: "Err", .. }"#);
+}
+
+//------------------ Structs -- rename
+
+#[derive(Models)]
+#[view(UserDto, omit(id), rename(password_hash => hash, created => created_at))]
+#[view(UserCamel, rename_all = "camelCase", rename(id => user_id))]
+struct UserRename {
+    id: i32,
+    display_name: String,
+    password_hash: String,
+    created: u64,
+}
+
+#[test]
+fn renamed_fields() {
+    let user = || UserRename {
+        id: 1,
+        display_name: "Cool Doode".to_string(),
+        password_hash: "hash".to_string(),
+        created: 42,
+    };
+
+    let dto: UserDto = user().into();
+    assert_eq!(dto.hash, "hash");
+    assert_eq!(dto.created_at, 42);
+    assert_eq!(dto.display_name, "Cool Doode");
+
+    let camel: UserCamel = user().into();
+    assert_eq!(camel.user_id, 1);
+    assert_eq!(camel.displayName, "Cool Doode");
+    assert_eq!(camel.passwordHash, "hash");
+}
+
+#[derive(Debug, Models)]
+#[view(ApiErrorRenamed, fields(NotFound, InternalServerError), rename(InternalServerError => Internal))]
+pub enum ApiErrorRename {
+    NotFound(String),
+    Unauthorized,
+    InternalServerError,
+}
+
+#[test]
+fn renamed_variants() {
+    let view = ApiErrorRenamed::try_from(ApiErrorRename::InternalServerError).unwrap();
+    assert!(matches!(view, ApiErrorRenamed::Internal));
+
+    let original: ApiErrorRename = ApiErrorRenamed::Internal.into();
+    assert!(matches!(original, ApiErrorRename::InternalServerError));
+}
+
+//------------------ Structs -- map
+
+fn to_rfc3339(secs: &u64) -> String {
+    format!("{secs}s")
+}
+
+fn from_rfc3339(value: &str) -> u64 {
+    value.trim_end_matches('s').parse().unwrap()
+}
+
+#[derive(Models)]
+#[view(UserMapped, map(id: String = ToString::to_string, created_at: String = (to_rfc3339, from_rfc3339), age: u64))]
+struct UserMap {
+    id: i32,
+    created_at: u64,
+    age: u32,
+}
+
+#[test]
+fn mapped_fields() {
+    let view: UserMapped = UserMap { id: 7, created_at: 60, age: 30 }.into();
+    assert_eq!(view.id, "7");
+    assert_eq!(view.created_at, "60s");
+    assert_eq!(view.age, 30u64);
+}
+
+#[derive(Debug, Models)]
+#[view(PaymentMapped, map(Card::last4: String = (ToString::to_string, |v: &String| v.parse().unwrap()), Cash::1: u64 = (|v: &u32| *v as u64, |v: &u64| *v as u32)))]
+pub enum PaymentMap {
+    Card { last4: u16, expiry: u32 },
+    Cash(u32, u32),
+}
+
+#[test]
+fn mapped_enum_fields() {
+    let view = PaymentMapped::try_from(PaymentMap::Card { last4: 1234, expiry: 1 }).unwrap();
+    assert!(matches!(&view, PaymentMapped::Card { last4, .. } if last4 == "1234"));
+
+    let original: PaymentMap = view.into();
+    assert!(matches!(original, PaymentMap::Card { last4: 1234, expiry: 1 }));
+
+    let view = PaymentMapped::try_from(PaymentMap::Cash(1, 2)).unwrap();
+    assert!(matches!(view, PaymentMapped::Cash(1, 2u64)));
+}
+
+#[derive(Debug, Clone, PartialEq, Models)]
+#[view(PaymentPublic, omit(Card::cvv, Bank::routing, Cash::0), default_omitted = true)]
+#[view(PaymentCards, fields(Card::last4, Cash), default_omitted = true)]
+pub enum PaymentSecret {
+    Card { last4: u16, cvv: u16 },
+    Bank { iban: String, routing: String },
+    Cash(u32, u32),
+    Free,
+}
+
+#[test]
+fn enum_variant_fields() {
+    let view = PaymentPublic::try_from(PaymentSecret::Card { last4: 1234, cvv: 999 }).unwrap();
+    assert!(matches!(view, PaymentPublic::Card { last4: 1234 }));
+
+    // Fields left out of the view are filled in by `Default` on the way back
+    let original: PaymentSecret = view.into();
+    assert_eq!(original, PaymentSecret::Card { last4: 1234, cvv: 0 });
+
+    // Kept fields of tuple variants are renumbered
+    let view = PaymentPublic::try_from(PaymentSecret::Cash(1, 2)).unwrap();
+    assert!(matches!(view, PaymentPublic::Cash(2)));
+    assert_eq!(PaymentSecret::from(view), PaymentSecret::Cash(0, 2));
+
+    // Naming a field of a variant selects the variant with only that field
+    let view = PaymentCards::try_from(PaymentSecret::Card { last4: 1234, cvv: 999 }).unwrap();
+    assert!(matches!(view, PaymentCards::Card { last4: 1234 }));
+    assert!(matches!(PaymentCards::try_from(PaymentSecret::Cash(1, 2)), Ok(PaymentCards::Cash(1, 2))));
+    assert_eq!(PaymentCards::try_from(PaymentSecret::Free).err().unwrap().variant, "Free");
+}
+
+//------------------ Structs -- compute
+
+#[derive(Models)]
+#[view(UserComputed, fields(first_name), compute(full_name: String = UserCompute::full_name, is_expired: bool = |u: &UserCompute| u.expires_at < 100))]
+struct UserCompute {
+    first_name: String,
+    last_name: String,
+    expires_at: u64,
+}
+
+impl UserCompute {
+    fn full_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+    }
+}
+
+#[test]
+fn computed_fields() {
+    let user = UserCompute {
+        first_name: "Cool".to_string(),
+        last_name: "Doode".to_string(),
+        expires_at: 50,
+    };
+
+    let view: UserComputed = user.into();
+    assert_eq!(view.first_name, "Cool");
+    assert_eq!(view.full_name, "Cool Doode");
+    assert!(view.is_expired);
+}
+
+#[derive(Models)]
+#[view(UserCard, fields(username, bio), rename(bio => about), compute(name_len: usize = |u: &UserBorrow<'_, T>| u.username.len()), ref = true, to_owned = true, ref_derive(Debug))]
+#[view(UserTag, fields(tag), ref = true)]
+struct UserBorrow<'a, T> {
+    username: String,
+    bio: Option<String>,
+    tag: &'a T,
+    password: String,
+}
+
+#[test]
+fn borrowed_ref_views() {
+    let tag = 7u8;
+    let user = UserBorrow {
+        username: "Cool".to_string(),
+        bio: None,
+        tag: &tag,
+        password: "secret".to_string(),
+    };
+
+    let profile = user.as_user_card();
+    assert!(std::ptr::eq(profile.username, &user.username));
+    assert_eq!(profile.about, &None);
+    assert_eq!(profile.name_len, 4);
+    assert!(format!("{profile:?}").starts_with("UserCardRef"));
+
+    let owned: UserCard = profile.to_owned();
+    assert_eq!(owned.username, "Cool");
+    assert_eq!(owned.name_len, 4);
+
+    let tagged: UserTagRef<'_, '_, u8> = UserTagRef::from(&user);
+    assert_eq!(**tagged.tag, 7);
+    assert_eq!(user.password, "secret");
+}
+
+#[derive(Models)]
+#[view(UserSummary, omit(history), rename(name => username), map(id: String = ToString::to_string, age: u64), compute(entries: usize = |u: &UserHistory<T>| u.history.len()), from_ref = true)]
+struct UserHistory<T> {
+    id: u32,
+    name: String,
+    age: u32,
+    history: Vec<T>,
+}
+
+#[test]
+fn from_borrowed_original() {
+    struct NotClone;
+    let user = UserHistory {
+        id: 1,
+        name: "Cool".to_string(),
+        age: 30,
+        history: vec![NotClone, NotClone],
+    };
+
+    let summary = UserSummary::from(&user);
+    assert_eq!(summary.id, "1");
+    assert_eq!(summary.username, "Cool");
+    assert_eq!(summary.age, 30);
+    assert_eq!(summary.entries, 2);
+    assert_eq!(user.name, "Cool");
+}
+
+#[derive(Debug, PartialEq, Models)]
+#[view(UserCreate, omit(id, created_at), rename(name => username))]
+#[view(UserDraft, fields(name, tags), default_omitted = true)]
+struct UserEntity<T> {
+    id: u32,
+    name: String,
+    tags: Vec<String>,
+    created_at: T,
+}
+
+#[test]
+fn view_into_original() {
+    let create = UserCreate {
+        username: "Cool".to_string(),
+        tags: vec!["new".to_string()],
+    };
+    let user = create.into_original(1, 100u64);
+    assert_eq!(user.id, 1);
+    assert_eq!(user.name, "Cool");
+    assert_eq!(user.created_at, 100);
+
+    let draft = UserDraft {
+        name: "Draft".to_string(),
+        tags: vec![],
+    };
+    let user: UserEntity<Option<u64>> = draft.into();
+    assert_eq!(user, UserEntity { id: 0, name: "Draft".to_string(), tags: vec![], created_at: None });
+}
+
+#[test]
+fn mapped_view_into_original() {
+    let view = UserMapped {
+        id: "7".to_string(),
+        created_at: "10s".to_string(),
+        age: 30,
+    };
+
+    // `id` and `age` have no reverse function so are given back like omitted fields
+    let user = view.into_original(7, 30);
+    assert_eq!(user.id, 7);
+    assert_eq!(user.created_at, 10);
+}
+
+#[test]
+fn tuple_view_into_original() {
+    let point = PointXZ(1.0, 3.0).into_original(2.0);
+    assert_eq!((point.0, point.1, point.2), (1.0, 2.0, 3.0));
+}
+
+//------------------ Structs -- nested
+
+#[derive(Clone, Models)]
+#[view(AddressPublic, omit(internal_note))]
+struct Address {
+    city: String,
+    internal_note: String,
+}
+
+#[derive(Clone, Models)]
+#[view(StaffSummary, fields(name))]
+struct Staff {
+    name: String,
+    salary: u32,
+}
+
+#[derive(Clone, Models)]
+#[view(
+    CompanyPublic,
+    omit(id),
+    nested(addresses = AddressPublic, offices = AddressPublic, manager = StaffSummary, founder = StaffSummary),
+    from_ref = true
+)]
+struct Company {
+    id: u32,
+    addresses: Vec<Address>,
+    offices: std::collections::HashMap<String, Option<Address>>,
+    manager: Option<Staff>,
+    founder: Box<Staff>,
+}
+
+#[test]
+fn nested_views() {
+    let address = |city: &str| Address { city: city.to_string(), internal_note: "secret".to_string() };
+    let company = Company {
+        id: 1,
+        addresses: vec![address("Leeds"), address("York")],
+        offices: [("hq".to_string(), Some(address("Hull"))), ("old".to_string(), None)].into(),
+        manager: None,
+        founder: Box::new(Staff { name: "Cool".to_string(), salary: 100 }),
+    };
+
+    let view = CompanyPublic::from(&company);
+    let cities: Vec<&str> = view.addresses.iter().map(|a| a.city.as_str()).collect();
+    assert_eq!(cities, vec!["Leeds", "York"]);
+    assert_eq!(view.offices["hq"].as_ref().unwrap().city, "Hull");
+    assert!(view.offices["old"].is_none());
+    assert!(view.manager.is_none());
+    assert_eq!(view.founder.name, "Cool");
+
+    // Nested fields can't be converted back, so they're given back like omitted fields
+    let view: CompanyPublic = company.clone().into();
+    let original = view.into_original(1, company.addresses, company.offices, company.manager, company.founder);
+    assert_eq!(original.founder.salary, 100);
+}